@@ -1,24 +1,38 @@
 use std::io::stdout;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crossterm::cursor::SetCursorStyle;
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{self, DisableBracketedPaste, EnableBracketedPaste, Event, KeyEvent};
 use crossterm::execute;
-use ratatui::{Frame, style::Stylize, text::ToLine, widgets::Padding};
+use ratatui::{
+    Frame,
+    style::Stylize,
+    text::{Line, ToLine},
+    widgets::Padding,
+};
 
-use crate::config::Config;
-use crate::page;
+use crate::config::{Config, keymap::Action};
+use crate::page::{self, session::Mode};
 use crate::utils::ROUNDED_BLOCK;
 
 const NO_CONFIG_ERROR: &str = r"No modes and/or sources found. 
 Consult the wiki at https://mahlquistj.github.io/octotype/docs/configuration/ for info on how to configure OctoType.";
 
+/// How long a busy page waits for quitting to be confirmed by pressing the key again
+const QUIT_CONFIRM_WINDOW: Duration = Duration::from_secs(2);
+/// How long a notice is shown in place of the exit hint
+const NOTICE_DURATION: Duration = Duration::from_secs(3);
+
 /// An app message
 pub enum Message {
     /// An error occurred
     Error(Box<dyn std::error::Error + Send>),
-    /// Show a specific page
+    /// Show a specific page, replacing the current one
     Show(page::Page),
+    /// Show a specific page, keeping the current one to return to with [`Message::Pop`]
+    Push(page::Page),
+    /// Return to the previous page, or to the main menu if there is none
+    Pop,
     /// Reset to the main menu
     Reset,
     /// Quit the application
@@ -28,58 +42,152 @@ pub enum Message {
 /// The app itself
 pub struct App {
     page: page::Page,
+    /// Pages to return to, most recent last
+    stack: Vec<page::Page>,
     config: Config,
+    /// When quitting from a busy page was requested, waiting for confirmation
+    quit_requested: Option<Instant>,
+    /// A message shown in place of the exit hint, and when it was shown
+    notice: Option<(Line<'static>, Instant)>,
 }
 
 impl App {
-    /// Creates a new `App`
-    pub fn new(config: Config) -> Self {
-        let page = if config.sources.is_empty() || config.modes.is_empty() {
+    /// Creates a new `App`. If `text` is given, a session typing it is started right away
+    pub fn new(config: Config, text: Option<String>) -> Self {
+        let page = if let Some(text) = text {
+            match page::Session::new(&config, Mode::from_text(text)) {
+                Ok(session) => session.into(),
+                Err(error) => page::Error::from(error).into(),
+            }
+        } else if config.sources.is_empty() || config.modes.is_empty() {
             page::Error::new(NO_CONFIG_ERROR.to_string()).into()
         } else {
-            page::Loading::load(&config, "Loading menu", |config| {
+            page::Loading::load(&config, config.tr("app.loading_menu"), |config, _task| {
                 page::Menu::new(config).map(|menu| Message::Show(menu.into()))
             })
             .into()
         };
-        Self { page, config }
+        Self {
+            page,
+            stack: Vec::new(),
+            config,
+            quit_requested: None,
+            notice: None,
+        }
     }
 
     /// Runs the app
     pub fn run(&mut self) -> std::io::Result<()> {
         let mut terminal = ratatui::init();
+        // The cursor shape outlives the app in the shell, also after a panic
+        let hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let _ = execute!(stdout(), SetCursorStyle::DefaultUserShape);
+            hook(info);
+        }));
+
+        // Pasted text arrives as a single event, instead of as keystrokes
+        execute!(stdout(), EnableBracketedPaste)?;
+        if let Some(style) = self.config.settings.theme.cursor.style.terminal_style() {
+            execute!(stdout(), style)?;
+        }
 
-        execute!(stdout(), SetCursorStyle::SteadyBar)?;
+        #[cfg(feature = "discord")]
+        let mut presence = crate::discord::Presence::new(&self.config);
+        #[cfg(feature = "discord")]
+        if crate::discord::Presence::is_missing_client_id(&self.config) {
+            let notice = Line::from(self.config.tr("app.discord_missing_client_id").to_string())
+                .fg(self.config.settings.theme.text.error);
+            self.notice = Some((notice, Instant::now()));
+        }
 
         loop {
             let event = event::poll(Duration::ZERO)?.then(event::read).transpose()?;
-            if let Some(message) = self.handle_events(event) {
-                match message {
-                    Message::Error(error) => self.page = page::Error::from(error).into(),
-                    Message::Show(page) => self.page = page,
-                    Message::Reset => {
-                        self.page = page::Loading::load(&self.config, "Loading menu", |config| {
-                            page::Menu::new(config).map(|menu| Message::Show(menu.into()))
-                        })
-                        .into()
-                    }
-                    Message::Quit => break,
-                }
+            if let Some(message) = self.handle_events(event)
+                && self.handle_message(message)
+            {
+                break;
             }
+            #[cfg(feature = "discord")]
+            presence.update(&self.page, &self.config);
             terminal.draw(|frame| self.draw(frame))?;
         }
 
         ratatui::restore();
+        execute!(
+            stdout(),
+            DisableBracketedPaste,
+            SetCursorStyle::DefaultUserShape
+        )?;
 
         Ok(())
     }
 
+    /// Applies a message to the app. Returns `true` if the app should quit
+    fn handle_message(&mut self, message: Message) -> bool {
+        match message {
+            Message::Error(error) => self.page = page::Error::from(error).into(),
+            Message::Show(page) => self.page = page,
+            Message::Push(page) => self.stack.push(std::mem::replace(&mut self.page, page)),
+            Message::Pop => {
+                if let Some(previous) = self.stack.pop() {
+                    self.page = previous;
+                } else if !matches!(self.page, page::Page::Menu(_)) {
+                    // Nothing to go back to, except the menu itself
+                    self.reset();
+                }
+            }
+            Message::Reset => self.reset(),
+            Message::Quit => return true,
+        }
+
+        false
+    }
+
+    /// Clears the page stack and loads the main menu
+    fn reset(&mut self) {
+        self.stack.clear();
+        let message = self.config.tr("app.loading_menu");
+        self.page = page::Loading::load(&self.config, message, |config, _task| {
+            page::Menu::new(config).map(|menu| Message::Show(menu.into()))
+        })
+        .into();
+    }
+
     /// Draws the next frame
     fn draw(&mut self, frame: &mut Frame) {
+        // The quit confirmation is shown in the frame, so it can't be left out then
+        if self.page.is_minimal() && !self.is_confirming_quit() {
+            self.page.render(frame, frame.area(), &self.config);
+            return;
+        }
+
+        let quit_key = self
+            .config
+            .settings
+            .keybindings
+            .hint(Action::Quit)
+            .to_uppercase();
+        let exit_hint = if self.is_confirming_quit() {
+            Line::from(
+                self.config
+                    .tr_with("app.confirm_exit", &[("key", &quit_key)]),
+            )
+            .fg(self.config.settings.theme.text.warning)
+        } else if let Some((notice, _)) = self
+            .notice
+            .as_ref()
+            .filter(|(_, shown)| shown.elapsed() < NOTICE_DURATION)
+        {
+            notice.clone()
+        } else {
+            Line::from(self.config.tr_with("app.exit", &[("key", &quit_key)]))
+        };
+
         let mut block = ROUNDED_BLOCK
             .padding(Padding::new(1, 1, 0, 0))
             .title_top("OCTOTYPE".to_line().bold().centered())
-            .title_top("<CTRL-Q> to exit".to_line().right_aligned());
+            .title_top(exit_hint.right_aligned());
 
         if let Some(top_msg) = self.page.render_top(&self.config) {
             block = block.title_top(top_msg);
@@ -99,7 +207,9 @@ impl App {
             .and_then(|event| {
                 self.page.handle_events(&event, &self.config).or_else(|| {
                     match event {
-                        Event::Key(key) => self.handle_key_event(key),
+                        Event::Key(key) if !self.page.captures_text(&key) => {
+                            self.handle_key_event(&key)
+                        }
                         _ => None, // Reserved for future event handling
                     }
                 })
@@ -107,12 +217,60 @@ impl App {
             .or_else(|| self.page.poll(&self.config))
     }
 
+    /// Whether quitting was requested on a busy page, and has to be confirmed
+    fn is_confirming_quit(&self) -> bool {
+        self.quit_requested
+            .is_some_and(|requested| requested.elapsed() < QUIT_CONFIRM_WINDOW)
+    }
+
+    /// Reads the theme from the config file again, and tells whether that worked
+    fn reload_theme(&mut self) {
+        let previous = self.config.settings.theme.cursor.style.terminal_style();
+        let notice = match self.config.reload_theme() {
+            Ok(()) => {
+                // The old cursor style is only cosmetic, so failing to replace it is fine
+                match self.config.settings.theme.cursor.style.terminal_style() {
+                    Some(style) => {
+                        let _ = execute!(stdout(), style);
+                    }
+                    None if previous.is_some() => {
+                        let _ = execute!(stdout(), SetCursorStyle::DefaultUserShape);
+                    }
+                    None => (),
+                }
+                Line::from(self.config.tr("app.theme_reloaded").to_string()).fg(self
+                    .config
+                    .settings
+                    .theme
+                    .text
+                    .success)
+            }
+            Err(error) => Line::from(
+                self.config
+                    .tr_with("app.theme_reload_failed", &[("error", &error)]),
+            )
+            .fg(self.config.settings.theme.text.error),
+        };
+        self.notice = Some((notice, Instant::now()));
+    }
+
     /// Global key events
-    const fn handle_key_event(&self, key: KeyEvent) -> Option<Message> {
-        match (key.code, key.modifiers) {
-            (KeyCode::Char('q'), KeyModifiers::CONTROL) => Some(Message::Quit),
-            (KeyCode::Esc, KeyModifiers::NONE) => Some(Message::Reset),
-            _ => None,
+    fn handle_key_event(&mut self, key: &KeyEvent) -> Option<Message> {
+        let keys = &self.config.settings.keybindings;
+        if keys.is(Action::Quit, key) {
+            // Quitting a busy page loses its progress, so the key has to be pressed twice
+            if self.page.is_busy() && !self.is_confirming_quit() {
+                self.quit_requested = Some(Instant::now());
+                return None;
+            }
+            Some(Message::Quit)
+        } else if keys.is(Action::PreviousPage, key) {
+            Some(Message::Pop)
+        } else if keys.is(Action::ReloadTheme, key) {
+            self.reload_theme();
+            None
+        } else {
+            None
         }
     }
 }
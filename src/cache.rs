@@ -0,0 +1,141 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// The cached output of a command source, for one set of parameters
+#[derive(Debug)]
+pub struct SourceCache {
+    path: PathBuf,
+    ttl: Duration,
+}
+
+impl SourceCache {
+    /// The cache of the source `name`, running `command` (with its parameters filled in)
+    pub fn new(directory: &Path, name: &str, command: &[String], ttl: Duration) -> Self {
+        // The hash may change between Rust versions, which only causes a cache miss
+        let mut hasher = DefaultHasher::new();
+        name.hash(&mut hasher);
+        command.hash(&mut hasher);
+
+        Self {
+            path: directory.join(format!("{:016x}", hasher.finish())),
+            ttl,
+        }
+    }
+
+    /// Returns the cached output, unless there is none or it has expired
+    pub fn read(&self) -> Option<String> {
+        let age = std::fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok()?
+            .elapsed()
+            .ok()?;
+
+        if age >= self.ttl {
+            return None;
+        }
+
+        std::fs::read_to_string(&self.path).ok()
+    }
+
+    /// Stores `output`. Failing to do so only means that the source runs again next time
+    pub fn write(&self, output: &str) {
+        if let Some(directory) = self.path.parent() {
+            let _ = std::fs::create_dir_all(directory);
+        }
+        let _ = std::fs::write(&self.path, output);
+    }
+}
+
+/// Whether `path` is named like a cached output
+fn is_cache_file(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| {
+                name.len() == 16 && name.chars().all(|character| character.is_ascii_hexdigit())
+            })
+}
+
+/// Removes every cached output.
+///
+/// The directory can be configured to be shared with other files, so only the cached outputs are
+/// removed, and the directory only if nothing else is left in it.
+pub fn clear(directory: &Path) -> std::io::Result<()> {
+    if !directory.exists() {
+        return Ok(());
+    }
+
+    for entry in std::fs::read_dir(directory)? {
+        let path = entry?.path();
+        if is_cache_file(&path) {
+            std::fs::remove_file(path)?;
+        }
+    }
+    // Fails if other files are left, which are none of our business
+    let _ = std::fs::remove_dir(directory);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{SourceCache, clear};
+
+    #[test]
+    fn cache_is_keyed_by_source_and_command() {
+        let directory = std::env::temp_dir().join(format!("octotype-cache-{}", std::process::id()));
+        let hour = Duration::from_secs(3600);
+        let command = |count: &str| vec!["words".to_string(), count.to_string()];
+
+        let cache = SourceCache::new(&directory, "Words", &command("10"), hour);
+        assert_eq!(cache.read(), None);
+        cache.write("some words");
+        assert_eq!(cache.read().as_deref(), Some("some words"));
+
+        // Same source, same parameters
+        let again = SourceCache::new(&directory, "Words", &command("10"), hour);
+        assert_eq!(again.read().as_deref(), Some("some words"));
+
+        // Other parameters, or another source
+        assert_eq!(
+            SourceCache::new(&directory, "Words", &command("20"), hour).read(),
+            None
+        );
+        assert_eq!(
+            SourceCache::new(&directory, "Quotes", &command("10"), hour).read(),
+            None
+        );
+
+        // Expired
+        assert_eq!(
+            SourceCache::new(&directory, "Words", &command("10"), Duration::ZERO).read(),
+            None
+        );
+
+        clear(&directory).unwrap();
+        assert_eq!(cache.read(), None);
+        assert!(!directory.exists());
+    }
+
+    #[test]
+    fn clearing_keeps_other_files() {
+        let directory =
+            std::env::temp_dir().join(format!("octotype-shared-cache-{}", std::process::id()));
+        let cache = SourceCache::new(&directory, "Words", &[], Duration::from_secs(3600));
+        cache.write("some words");
+        let other = directory.join("other-application");
+        std::fs::write(&other, "not ours").unwrap();
+
+        clear(&directory).unwrap();
+
+        assert_eq!(cache.read(), None);
+        assert!(other.exists());
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,84 @@
+use std::{
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+use derive_more::From;
+use rand::{Rng, rng};
+use thiserror::Error;
+
+use crate::{
+    config::{
+        Config, SourceConfig,
+        parameters::{ParameterError, ParameterValues},
+    },
+    page::session::{CreateModeError, FetchError, Source},
+};
+
+/// Maximum time a source may take to produce its output
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+/// Time between polls of a running source
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Error, From)]
+pub enum CheckSourceError {
+    #[error("Invalid parameter: {0}")]
+    Parameter(ParameterError),
+
+    #[error("{0}")]
+    Create(CreateModeError),
+
+    #[error("{0}")]
+    Fetch(FetchError),
+
+    #[error("Timed out after {}s", FETCH_TIMEOUT.as_secs())]
+    Timeout,
+}
+
+/// Runs every available source once with its default parameters, printing the result of each.
+///
+/// Returns `true` if all sources passed.
+pub fn check_sources(config: &Config) -> bool {
+    let mut all_passed = true;
+
+    for source in config.list_sources() {
+        let name = source.meta.name.clone();
+        match check_source(config, source) {
+            Ok(()) => println!("[PASS] {name}"),
+            Err(error) => {
+                all_passed = false;
+                println!("[FAIL] {name}: {error}");
+            }
+        }
+    }
+
+    all_passed
+}
+
+/// Checks that the required tools of the source exist, and that it produces output in time
+fn check_source(config: &Config, source: SourceConfig) -> Result<(), CheckSourceError> {
+    let parameters = source
+        .parameters
+        .iter()
+        .map(|(name, definition)| {
+            definition
+                .clone()
+                .into_parameter(true)
+                .map(|parameter| (name.clone(), parameter))
+        })
+        .collect::<Result<ParameterValues, _>>()?;
+
+    let seed = config.seed.unwrap_or_else(|| rng().random());
+    let parameters = parameters.with_seed(seed);
+    let mut source = Source::from_config(config, source, &parameters, seed)?.without_cache();
+
+    let deadline = Instant::now() + FETCH_TIMEOUT;
+    while Instant::now() < deadline {
+        if source.try_fetch()?.is_some() {
+            return Ok(());
+        }
+        sleep(POLL_INTERVAL);
+    }
+
+    Err(CheckSourceError::Timeout)
+}
@@ -1,4 +1,11 @@
-use std::{collections::HashMap, net::TcpStream, path::PathBuf, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    net::TcpStream,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
 use derive_more::{Deref, From};
 use directories::ProjectDirs;
@@ -12,13 +19,24 @@ use thiserror::Error;
 pub use mode::ModeConfig;
 pub use source::SourceConfig;
 
-use crate::config::{stats::StatisticsConfig, theme::Theme};
+use crate::config::{
+    cache::CacheConfig, discord::DiscordConfig, keymap::KeyMap, language::Language,
+    multi_source::MultiSourceConfig, session::SessionConfig, stats::StatisticsConfig,
+    stats_layout::StatsLayoutConfig, theme::Theme,
+};
 use crate::statistics::{StatisticsError, StatisticsManager};
 
+pub mod cache;
+pub mod discord;
+pub mod keymap;
+pub mod language;
 pub mod mode;
+pub mod multi_source;
 pub mod parameters;
+pub mod session;
 pub mod source;
 pub mod stats;
+pub mod stats_layout;
 pub mod theme;
 
 #[derive(Debug, From, Error)]
@@ -42,19 +60,50 @@ pub enum ConfigError {
 
     #[error("Failed to initialize statistics: {0}")]
     Statistics(StatisticsError),
+
+    #[error("Invalid keybindings: {0}")]
+    Keybindings(keymap::KeyMapError),
+
+    #[error("Failed to load language: {0}")]
+    Language(language::LanguageError),
+}
+
+#[derive(Debug, From, Error)]
+pub enum ExportConfigError {
+    #[error("Failed to write config: {0}")]
+    Write(std::io::Error),
+
+    #[error("Failed to serialize config: {0}")]
+    Serialize(toml::ser::Error),
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Settings {
     pub theme: theme::Theme,
     pub statistic: stats::StatisticsConfig,
+    pub discord: DiscordConfig,
+    pub keybindings: KeyMap,
+    pub multi_source: MultiSourceConfig,
+    pub cache: CacheConfig,
+    pub session: SessionConfig,
+    pub stats_layout: StatsLayoutConfig,
     sources_dir: Option<PathBuf>,
     modes_dir: Option<PathBuf>,
+    exports_dir: Option<PathBuf>,
+    languages_dir: Option<PathBuf>,
+    /// The language of the UI, e.g. `"en"`
+    pub language: String,
     pub words_per_line: usize,
     pub show_ghost_lines: usize,
     #[serde(default)]
     pub ghost_opacity: Vec<f32>,
     pub disable_ghost_fade: bool,
+    pub typed_context_lines: Option<usize>,
+    pub bell_on_error: bool,
+    /// Shows a panel with this many of the latest mistakes during a session
+    pub recent_errors: Option<usize>,
+    /// Ends a session after this many seconds without a keystroke
+    pub idle_timeout_seconds: Option<u64>,
 }
 
 impl Default for Settings {
@@ -62,12 +111,25 @@ impl Default for Settings {
         Self {
             theme: Theme::default(),
             statistic: StatisticsConfig::default(),
+            discord: DiscordConfig::default(),
+            keybindings: KeyMap::default(),
+            multi_source: MultiSourceConfig::default(),
+            cache: CacheConfig::default(),
+            session: SessionConfig::default(),
+            stats_layout: StatsLayoutConfig::default(),
             sources_dir: None,
             modes_dir: None,
+            exports_dir: None,
+            languages_dir: None,
+            language: language::FALLBACK.to_string(),
             words_per_line: 5,
             show_ghost_lines: 3,
             ghost_opacity: get_evenly_spread_values(3),
             disable_ghost_fade: false,
+            typed_context_lines: None,
+            bell_on_error: false,
+            recent_errors: None,
+            idle_timeout_seconds: None,
         }
     }
 }
@@ -85,13 +147,21 @@ fn is_online() -> bool {
 #[derive(Clone, Debug, Deref, Default, Serialize)]
 pub struct Config(Arc<InnerConfig>);
 
-#[derive(Debug, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct InnerConfig {
     pub settings: Settings,
     pub modes: HashMap<String, ModeConfig>,
     pub sources: HashMap<String, SourceConfig>,
     #[serde(skip)]
     pub statistics_manager: Option<StatisticsManager>,
+    /// Seed given on the command line, overriding the seed of every mode
+    #[serde(skip)]
+    pub seed: Option<u64>,
+    #[serde(skip)]
+    pub language: Language,
+    /// The `config.toml` the settings were loaded from, which might not exist
+    #[serde(skip)]
+    settings_file: PathBuf,
 }
 
 impl Config {
@@ -117,10 +187,32 @@ impl Config {
         self.settings.sources_dir.as_ref().unwrap()
     }
 
-    pub fn get(override_path: Option<PathBuf>) -> Result<Self, ConfigError> {
-        // Grab default configuration
-        let mut settings = Figment::from(Serialized::defaults(Settings::default()));
+    pub fn exports_dir(&self) -> &PathBuf {
+        self.settings.exports_dir.as_ref().unwrap()
+    }
 
+    pub fn cache_dir(&self) -> &PathBuf {
+        self.settings.cache.directory.as_ref().unwrap()
+    }
+
+    /// Returns the UI string of `key` in the configured language
+    pub fn tr<'a>(&'a self, key: &'a str) -> &'a str {
+        self.language.get(key)
+    }
+
+    /// Returns the UI string of `key` in the configured language, with its `{name}` placeholders
+    /// filled in from `args`
+    pub fn tr_with(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        self.language.format(key, args)
+    }
+
+    /// Loads the config. If `bootstrap` is set, the default modes and sources are written to
+    /// their directories when those are empty
+    pub fn get(
+        override_path: Option<PathBuf>,
+        bootstrap: bool,
+        seed: Option<u64>,
+    ) -> Result<Self, ConfigError> {
         // Check for toml file location
         let config_dir = override_path
             .or_else(|| {
@@ -139,18 +231,15 @@ impl Config {
             settings_toml.push("config.toml");
         }
 
-        if settings_toml.exists() {
-            settings = settings.merge(Toml::file(settings_toml));
-        }
-
-        let mut settings: Settings = settings.extract().map_err(Box::new)?;
+        let mut settings = load_settings(&settings_toml)?;
+        settings.keybindings.validate()?;
 
         let sources_dir = settings.sources_dir.clone().unwrap_or_else(|| {
             let mut dir = config_dir.clone();
             dir.push("sources");
             dir
         });
-        let sources = source::get_sources(&sources_dir)?;
+        let sources = source::get_sources(&sources_dir, bootstrap)?;
         settings.sources_dir = Some(sources_dir);
 
         let modes_dir = settings.modes_dir.clone().unwrap_or_else(|| {
@@ -158,13 +247,35 @@ impl Config {
             dir.push("modes");
             dir
         });
-        let modes = mode::get_modes(&modes_dir)?;
+        let modes = mode::get_modes(&modes_dir, bootstrap)?;
         settings.modes_dir = Some(modes_dir);
 
+        if settings.exports_dir.is_none() {
+            let mut dir = config_dir.clone();
+            dir.push("exports");
+            settings.exports_dir = Some(dir);
+        }
+
+        if settings.cache.directory.is_none() {
+            let mut dir = config_dir.clone();
+            dir.push("cache");
+            settings.cache.directory = Some(dir);
+        }
+
+        let languages_dir = settings.languages_dir.clone().unwrap_or_else(|| {
+            let mut dir = config_dir.clone();
+            dir.push("languages");
+            dir
+        });
+        let language = Language::load(&languages_dir, &settings.language)?;
+        settings.languages_dir = Some(languages_dir);
+
         if settings.ghost_opacity.len() != settings.show_ghost_lines {
             settings.ghost_opacity = get_evenly_spread_values(settings.show_ghost_lines);
         }
 
+        settings.theme.apply_preset();
+
         // Initialize statistics manager if saving is enabled
         let statistics_manager = if settings.statistic.save_enabled {
             let stats_dir = settings.statistic.directory.clone().unwrap_or_else(|| {
@@ -172,7 +283,12 @@ impl Config {
                 dir.push("statistics");
                 dir
             });
-            Some(StatisticsManager::new(stats_dir)?)
+            let manager = StatisticsManager::new(stats_dir)?;
+            Some(if settings.statistic.store_input_history {
+                manager
+            } else {
+                manager.without_input_history()
+            })
         } else {
             None
         };
@@ -182,8 +298,91 @@ impl Config {
             sources,
             modes,
             statistics_manager,
+            seed,
+            language,
+            settings_file: settings_toml,
         })))
     }
+
+    /// Reads the theme from the config file again, keeping the rest of the loaded config. The
+    /// theme is left as it is if the file fails to parse
+    pub fn reload_theme(&mut self) -> Result<(), ConfigError> {
+        let mut theme = load_settings(&self.settings_file)?.theme;
+        theme.apply_preset();
+        Arc::make_mut(&mut self.0).settings.theme = theme;
+        Ok(())
+    }
+
+    /// Turns off what only makes sense with someone at the terminal: The bell, which is written to
+    /// stdout, and the idle timeout
+    pub fn headless(mut self) -> Self {
+        let settings = &mut Arc::make_mut(&mut self.0).settings;
+        settings.bell_on_error = false;
+        settings.idle_timeout_seconds = None;
+        self
+    }
+
+    /// Changes the loaded config, for tests
+    #[cfg(test)]
+    pub fn with(mut self, change: impl FnOnce(&mut InnerConfig)) -> Self {
+        change(Arc::make_mut(&mut self.0));
+        self
+    }
+}
+
+/// Loads the settings of `settings_toml` on top of the defaults
+fn load_settings(settings_toml: &Path) -> Result<Settings, ConfigError> {
+    let mut settings = Figment::from(Serialized::defaults(Settings::default()));
+    if settings_toml.exists() {
+        settings = settings.merge(Toml::file(settings_toml));
+    }
+    Ok(settings.extract().map_err(Box::new)?)
+}
+
+impl Config {
+    /// Writes the loaded settings, modes and sources (including defaults) to `dir`, laid out
+    /// like a config directory. Existing files are overwritten
+    pub fn export(&self, dir: &Path) -> Result<(), ExportConfigError> {
+        let mut settings = toml::Table::try_from(&self.settings)?;
+        // These are resolved relative to the loaded config directory. The exported config uses its
+        // own directory instead
+        for key in ["sources_dir", "modes_dir", "exports_dir", "languages_dir"] {
+            settings.remove(key);
+        }
+        if let Some(toml::Value::Table(cache)) = settings.get_mut("cache") {
+            cache.remove("directory");
+        }
+
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(dir.join("config.toml"), toml::to_string_pretty(&settings)?)?;
+        write_configs(&dir.join("modes"), &self.modes)?;
+        write_configs(&dir.join("sources"), &self.sources)
+    }
+}
+
+/// Writes each config to `<name>.toml` in `dir`
+fn write_configs<T: Serialize>(
+    dir: &Path,
+    configs: &HashMap<String, T>,
+) -> Result<(), ExportConfigError> {
+    std::fs::create_dir_all(dir)?;
+    for (name, config) in configs {
+        let file_name = name
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+        std::fs::write(
+            dir.join(format!("{file_name}.toml")),
+            toml::to_string_pretty(config)?,
+        )?;
+    }
+    Ok(())
 }
 
 fn get_evenly_spread_values(num_items: usize) -> Vec<f32> {
@@ -208,3 +407,104 @@ fn get_evenly_spread_values(num_items: usize) -> Vec<f32> {
 
     values
 }
+
+#[cfg(test)]
+mod test {
+    use ratatui::style::Color;
+
+    use super::{Config, load_settings, mode, source, write_configs};
+
+    #[test]
+    fn exported_modes_and_sources_reload_identically() {
+        let directory =
+            std::env::temp_dir().join(format!("octotype-export-{}", std::process::id()));
+        let modes = mode::create_default_modes();
+        let sources = source::create_default_sources();
+
+        write_configs(&directory.join("modes"), &modes).unwrap();
+        write_configs(&directory.join("sources"), &sources).unwrap();
+
+        let reloaded_modes = mode::get_modes(&directory.join("modes"), false).unwrap();
+        let reloaded_sources = source::get_sources(&directory.join("sources"), false).unwrap();
+
+        assert_eq!(reloaded_modes.len(), modes.len());
+        for mode in modes.values() {
+            assert_eq!(
+                toml::Table::try_from(&reloaded_modes[&mode.meta.name]).unwrap(),
+                toml::Table::try_from(mode).unwrap()
+            );
+        }
+        assert_eq!(reloaded_sources.len(), sources.len());
+        for source in sources.values() {
+            assert_eq!(
+                toml::Table::try_from(&reloaded_sources[&source.meta.name]).unwrap(),
+                toml::Table::try_from(source).unwrap()
+            );
+        }
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn exported_settings_reload_identically() {
+        let directory =
+            std::env::temp_dir().join(format!("octotype-export-settings-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(
+            directory.join("config.toml"),
+            "bell_on_error = true\n[theme.text]\nsuccess = \"cyan\"\n",
+        )
+        .unwrap();
+        let config = Config::get(Some(directory.clone()), false, None).unwrap();
+        let exported = directory.join("exported");
+
+        config.export(&exported).unwrap();
+        let written: toml::Table =
+            toml::from_str(&std::fs::read_to_string(exported.join("config.toml")).unwrap())
+                .unwrap();
+        let reloaded = load_settings(&exported.join("config.toml")).unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        for key in ["sources_dir", "modes_dir", "exports_dir", "languages_dir"] {
+            assert!(!written.contains_key(key), "{key} was exported");
+        }
+        assert!(
+            !written["cache"]
+                .as_table()
+                .unwrap()
+                .contains_key("directory")
+        );
+
+        let mut expected = toml::Table::try_from(&config.settings).unwrap();
+        for key in ["sources_dir", "modes_dir", "exports_dir", "languages_dir"] {
+            expected.remove(key);
+        }
+        if let Some(toml::Value::Table(cache)) = expected.get_mut("cache") {
+            cache.remove("directory");
+        }
+        assert_eq!(toml::Table::try_from(&reloaded).unwrap(), expected);
+        assert!(reloaded.bell_on_error);
+        assert_eq!(reloaded.theme.text.success, Color::Cyan);
+    }
+
+    #[test]
+    fn reloading_keeps_the_theme_on_errors() {
+        let directory = std::env::temp_dir().join(format!("octotype-theme-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let settings = directory.join("config.toml");
+        std::fs::write(&settings, "[theme.text]\nsuccess = \"cyan\"\n").unwrap();
+
+        let mut config = Config::get(Some(directory.clone()), false, None).unwrap();
+        assert_eq!(config.settings.theme.text.success, Color::Cyan);
+
+        std::fs::write(&settings, "[theme.text]\nsuccess = \"magenta\"\n").unwrap();
+        config.reload_theme().unwrap();
+        assert_eq!(config.settings.theme.text.success, Color::Magenta);
+
+        std::fs::write(&settings, "[theme.text]\nsuccess = 42\n").unwrap();
+        assert!(config.reload_theme().is_err());
+        assert_eq!(config.settings.theme.text.success, Color::Magenta);
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
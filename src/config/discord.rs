@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// Settings for the Discord Rich Presence (requires the `discord` feature)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DiscordConfig {
+    pub enabled: bool,
+    /// Id of the Discord application the presence is shown for
+    pub client_id: Option<String>,
+    pub show_mode: bool,
+    pub show_wpm: bool,
+    pub show_time: bool,
+}
+
+impl Default for DiscordConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            client_id: None,
+            show_mode: true,
+            show_wpm: true,
+            show_time: true,
+        }
+    }
+}
@@ -0,0 +1,427 @@
+use std::fmt::Display;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, IntoEnumIterator};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum KeyMapError {
+    #[error("Invalid key '{0}'")]
+    InvalidKey(String),
+
+    #[error("Key '{key}' is bound to both '{first}' and '{second}'")]
+    Duplicate {
+        key: KeyBinding,
+        first: Action,
+        second: Action,
+    },
+}
+
+/// An action that can be bound to keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, strum::Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum Action {
+    Quit,
+    PreviousPage,
+    Up,
+    Down,
+    Left,
+    Right,
+    Select,
+    Back,
+    NextView,
+    ToggleMinimal,
+    ReloadTheme,
+    Preview,
+    Export,
+    Keystrokes,
+    Tags,
+    Practice,
+    Review,
+    Heatmap,
+    Delete,
+    Compare,
+    Smoothing,
+}
+
+/// A page with keys of its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    Menu,
+    Error,
+    Stats,
+    Review,
+    History,
+}
+
+impl Action {
+    /// The pages the action is used on, or `None` if it works on every page
+    const fn scope(self) -> Option<&'static [Scope]> {
+        match self {
+            Self::Quit | Self::PreviousPage | Self::ToggleMinimal | Self::ReloadTheme => None,
+            Self::Up | Self::Down => Some(&[Scope::Menu, Scope::Review, Scope::History]),
+            Self::Left | Self::Right | Self::Preview => Some(&[Scope::Menu]),
+            Self::Select => Some(&[Scope::Menu, Scope::Error, Scope::Stats, Scope::History]),
+            Self::Back => Some(&[Scope::Menu, Scope::Review]),
+            Self::Export | Self::Keystrokes | Self::Tags | Self::Practice => Some(&[Scope::Stats]),
+            Self::Review => Some(&[Scope::Stats, Scope::Review]),
+            Self::Heatmap => Some(&[Scope::Review]),
+            Self::NextView | Self::Delete | Self::Compare | Self::Smoothing => {
+                Some(&[Scope::History])
+            }
+        }
+    }
+
+    /// Whether both actions can be triggered on the same page
+    fn overlaps(self, other: Self) -> bool {
+        match (self.scope(), other.scope()) {
+            (Some(scope), Some(other)) => scope.iter().any(|page| other.contains(page)),
+            _ => true,
+        }
+    }
+}
+
+/// The keys bound to each [`Action`]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct KeyMap {
+    /// Quits the application
+    pub quit: Vec<KeyBinding>,
+    /// Returns to the previous page (or menu state), or to the main menu if there is none
+    pub previous_page: Vec<KeyBinding>,
+    pub up: Vec<KeyBinding>,
+    pub down: Vec<KeyBinding>,
+    pub left: Vec<KeyBinding>,
+    pub right: Vec<KeyBinding>,
+    pub select: Vec<KeyBinding>,
+    /// Goes back to the previous menu
+    pub back: Vec<KeyBinding>,
+    /// Switches to the next view of the history
+    pub next_view: Vec<KeyBinding>,
+    /// Shows only the text during a session
+    pub toggle_minimal: Vec<KeyBinding>,
+    /// Reads the theme from the config file again
+    pub reload_theme: Vec<KeyBinding>,
+    /// Previews the text of the selected parameters in the menu
+    pub preview: Vec<KeyBinding>,
+    /// Exports the statistics of a session
+    pub export: Vec<KeyBinding>,
+    /// Shows the speed of every keystroke in the WPM chart of the statistics
+    pub keystrokes: Vec<KeyBinding>,
+    /// Edits the tags of a saved session
+    pub tags: Vec<KeyBinding>,
+    /// Starts a session typing the failed words of the last one
+    pub practice: Vec<KeyBinding>,
+    /// Opens the review of the typed text, or closes it again
+    pub review: Vec<KeyBinding>,
+    /// Colors the review by the time spent on each character
+    pub heatmap: Vec<KeyBinding>,
+    /// Deletes the selected session of the history, once pressed twice
+    pub delete: Vec<KeyBinding>,
+    /// Marks the selected session of the history to compare
+    pub compare: Vec<KeyBinding>,
+    /// Smooths the trend charts of the history
+    pub smoothing: Vec<KeyBinding>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        let bind = |keys: &[(KeyCode, KeyModifiers)]| {
+            keys.iter()
+                .map(|(code, modifiers)| KeyBinding {
+                    code: *code,
+                    modifiers: *modifiers,
+                })
+                .collect()
+        };
+        let none = KeyModifiers::NONE;
+
+        Self {
+            quit: bind(&[(KeyCode::Char('q'), KeyModifiers::CONTROL)]),
+            previous_page: bind(&[(KeyCode::Esc, none)]),
+            up: bind(&[(KeyCode::Up, none), (KeyCode::Char('k'), none)]),
+            down: bind(&[(KeyCode::Down, none), (KeyCode::Char('j'), none)]),
+            left: bind(&[(KeyCode::Left, none), (KeyCode::Char('h'), none)]),
+            right: bind(&[(KeyCode::Right, none), (KeyCode::Char('l'), none)]),
+            select: bind(&[(KeyCode::Enter, none)]),
+            back: bind(&[(KeyCode::Backspace, none)]),
+            next_view: bind(&[(KeyCode::Tab, none)]),
+            toggle_minimal: bind(&[(KeyCode::Char('f'), KeyModifiers::CONTROL)]),
+            reload_theme: bind(&[(KeyCode::Char('r'), KeyModifiers::CONTROL)]),
+            preview: bind(&[(KeyCode::Char('p'), none)]),
+            export: bind(&[(KeyCode::Char('e'), none)]),
+            keystrokes: bind(&[(KeyCode::Char('k'), none)]),
+            tags: bind(&[(KeyCode::Char('t'), none)]),
+            practice: bind(&[(KeyCode::Char('p'), none)]),
+            review: bind(&[(KeyCode::Char('r'), none)]),
+            heatmap: bind(&[(KeyCode::Char('h'), none)]),
+            delete: bind(&[(KeyCode::Char('d'), none)]),
+            compare: bind(&[(KeyCode::Char('c'), none)]),
+            smoothing: bind(&[(KeyCode::Char('s'), none)]),
+        }
+    }
+}
+
+impl KeyMap {
+    pub fn bindings(&self, action: Action) -> &[KeyBinding] {
+        match action {
+            Action::Quit => &self.quit,
+            Action::PreviousPage => &self.previous_page,
+            Action::Up => &self.up,
+            Action::Down => &self.down,
+            Action::Left => &self.left,
+            Action::Right => &self.right,
+            Action::Select => &self.select,
+            Action::Back => &self.back,
+            Action::NextView => &self.next_view,
+            Action::ToggleMinimal => &self.toggle_minimal,
+            Action::ReloadTheme => &self.reload_theme,
+            Action::Preview => &self.preview,
+            Action::Export => &self.export,
+            Action::Keystrokes => &self.keystrokes,
+            Action::Tags => &self.tags,
+            Action::Practice => &self.practice,
+            Action::Review => &self.review,
+            Action::Heatmap => &self.heatmap,
+            Action::Delete => &self.delete,
+            Action::Compare => &self.compare,
+            Action::Smoothing => &self.smoothing,
+        }
+    }
+
+    /// Checks whether `key` is bound to `action`
+    pub fn is(&self, action: Action, key: &KeyEvent) -> bool {
+        self.bindings(action)
+            .iter()
+            .any(|binding| binding.matches(key))
+    }
+
+    /// Returns the first key bound to `action`, for display in key hints
+    pub fn hint(&self, action: Action) -> String {
+        self.bindings(action)
+            .first()
+            .map_or_else(|| "unbound".to_string(), ToString::to_string)
+    }
+
+    /// Ensures that no key is bound to more than one action of the same page
+    pub fn validate(&self) -> Result<(), KeyMapError> {
+        let mut seen: Vec<(&KeyBinding, Action)> = Vec::new();
+
+        for action in Action::iter() {
+            for binding in self.bindings(action) {
+                if let Some((_, first)) = seen
+                    .iter()
+                    .find(|(other, first)| *other == binding && first.overlaps(action))
+                {
+                    return Err(KeyMapError::Duplicate {
+                        key: binding.clone(),
+                        first: *first,
+                        second: action,
+                    });
+                }
+                seen.push((binding, action));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A key, optionally combined with modifiers. Written as e.g. `"k"`, `"enter"` or `"ctrl-q"`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    fn matches(&self, key: &KeyEvent) -> bool {
+        // Shift is already reflected in the character itself (e.g. 'K')
+        let modifiers = match key.code {
+            KeyCode::Char(_) => key.modifiers.difference(KeyModifiers::SHIFT),
+            _ => key.modifiers,
+        };
+        self.code == key.code && self.modifiers == modifiers
+    }
+}
+
+impl TryFrom<String> for KeyBinding {
+    type Error = KeyMapError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let invalid = || KeyMapError::InvalidKey(value.clone());
+
+        // A single character is always a key, even if it's the separator
+        let (modifier_names, key) = if value.chars().count() == 1 {
+            ("", value.as_str())
+        } else {
+            value.rsplit_once('-').unwrap_or(("", value.as_str()))
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for name in modifier_names.split('-').filter(|name| !name.is_empty()) {
+            modifiers |= match name.to_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(invalid()),
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(character), None) => KeyCode::Char(character),
+            _ => match key.to_lowercase().as_str() {
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "delete" => KeyCode::Delete,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                _ => return Err(invalid()),
+            },
+        };
+
+        Ok(Self { code, modifiers })
+    }
+}
+
+impl From<KeyBinding> for String {
+    fn from(value: KeyBinding) -> Self {
+        value.to_string()
+    }
+}
+
+impl Display for KeyBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "ctrl"),
+            (KeyModifiers::ALT, "alt"),
+            (KeyModifiers::SHIFT, "shift"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{name}-")?;
+            }
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(character) => write!(f, "{character}"),
+            KeyCode::Enter => write!(f, "enter"),
+            KeyCode::Esc => write!(f, "esc"),
+            KeyCode::Tab => write!(f, "tab"),
+            KeyCode::Backspace => write!(f, "backspace"),
+            KeyCode::Delete => write!(f, "delete"),
+            KeyCode::Up => write!(f, "up"),
+            KeyCode::Down => write!(f, "down"),
+            KeyCode::Left => write!(f, "left"),
+            KeyCode::Right => write!(f, "right"),
+            KeyCode::Home => write!(f, "home"),
+            KeyCode::End => write!(f, "end"),
+            KeyCode::PageUp => write!(f, "pageup"),
+            KeyCode::PageDown => write!(f, "pagedown"),
+            // Only the keys above can be parsed
+            other => write!(f, "{other}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use super::{Action, KeyBinding, KeyMap, KeyMapError};
+
+    fn parse(key: &str) -> KeyBinding {
+        KeyBinding::try_from(key.to_string()).unwrap()
+    }
+
+    #[test]
+    fn parse_and_display_roundtrip() {
+        for key in [
+            "k",
+            "-",
+            "enter",
+            "ctrl-q",
+            "ctrl-alt-x",
+            "space",
+            "pagedown",
+        ] {
+            assert_eq!(parse(key).to_string(), key);
+        }
+
+        assert_eq!(parse("Ctrl-Enter").to_string(), "ctrl-enter");
+        assert!(KeyBinding::try_from("hyper-q".to_string()).is_err());
+        assert!(KeyBinding::try_from("nope".to_string()).is_err());
+    }
+
+    #[test]
+    fn default_matches_current_behaviour() {
+        let keymap = KeyMap::default();
+        keymap.validate().unwrap();
+
+        let key = |code, modifiers| KeyEvent::new(code, modifiers);
+        assert!(keymap.is(
+            Action::Quit,
+            &key(KeyCode::Char('q'), KeyModifiers::CONTROL)
+        ));
+        assert!(!keymap.is(Action::Quit, &key(KeyCode::Char('q'), KeyModifiers::NONE)));
+        assert!(keymap.is(Action::Up, &key(KeyCode::Char('k'), KeyModifiers::NONE)));
+        assert!(keymap.is(Action::Up, &key(KeyCode::Up, KeyModifiers::NONE)));
+        assert!(!keymap.is(
+            Action::PreviousPage,
+            &key(KeyCode::Esc, KeyModifiers::CONTROL)
+        ));
+    }
+
+    #[test]
+    fn keys_can_be_shared_between_pages() {
+        // Both are used in the menu
+        let keymap = KeyMap {
+            preview: vec![parse("k")],
+            ..KeyMap::default()
+        };
+        assert!(matches!(
+            keymap.validate(),
+            Err(KeyMapError::Duplicate {
+                first: Action::Up,
+                second: Action::Preview,
+                ..
+            })
+        ));
+
+        // Only one of them is used on the history page
+        let keymap = KeyMap {
+            delete: vec![parse("p")],
+            ..KeyMap::default()
+        };
+        keymap.validate().unwrap();
+    }
+
+    #[test]
+    fn duplicate_bindings_are_rejected() {
+        let keymap = KeyMap {
+            back: vec![parse("esc")],
+            ..KeyMap::default()
+        };
+
+        assert!(matches!(
+            keymap.validate(),
+            Err(KeyMapError::Duplicate {
+                first: Action::PreviousPage,
+                second: Action::Back,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,156 @@
+use std::{collections::HashMap, fmt::Display, path::Path};
+
+use derive_more::From;
+use thiserror::Error;
+
+/// Languages shipped with OctoType, as `(name, strings)`
+const BUILT_IN: [(&str, &str); 2] = [
+    ("en", include_str!("language/en.toml")),
+    ("de", include_str!("language/de.toml")),
+];
+
+/// The language every other language falls back to
+pub const FALLBACK: &str = "en";
+
+#[derive(Debug, From, Error)]
+pub enum LanguageError {
+    #[error("Unknown language '{0}' - Add it as '{0}.toml' to the languages directory")]
+    #[from(skip)]
+    Unknown(String),
+
+    #[error("Failed to read language file: {0}")]
+    ReadFile(std::io::Error),
+
+    #[error("Failed to parse language file: {0}")]
+    ParseFile(toml::de::Error),
+}
+
+/// The UI strings of a language, by key (e.g. `"menu.select_mode"`).
+///
+/// Keys missing from the language are looked up in English instead.
+#[derive(Debug, Default, Clone)]
+pub struct Language {
+    strings: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+impl Language {
+    /// Loads the language `name`. A `<name>.toml` in `from_dir` takes precedence over the
+    /// built-in languages
+    pub fn load(from_dir: &Path, name: &str) -> Result<Self, LanguageError> {
+        Ok(Self {
+            strings: load_strings(from_dir, name)?,
+            fallback: load_strings(from_dir, FALLBACK)?,
+        })
+    }
+
+    /// Returns the string of `key`. Unknown keys are returned as they are
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .map_or(key, String::as_str)
+    }
+
+    /// Returns the string of `key`, with every `{name}` replaced by its value in `args`
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        args.iter()
+            .fold(self.get(key).to_string(), |text, (name, value)| {
+                text.replace(&format!("{{{name}}}"), &value.to_string())
+            })
+    }
+}
+
+fn load_strings(from_dir: &Path, name: &str) -> Result<HashMap<String, String>, LanguageError> {
+    let file = from_dir.join(format!("{name}.toml"));
+    let content = if file.is_file() {
+        std::fs::read_to_string(file)?
+    } else {
+        BUILT_IN
+            .iter()
+            .find(|(built_in, _)| *built_in == name)
+            .map(|(_, content)| (*content).to_string())
+            .ok_or_else(|| LanguageError::Unknown(name.to_string()))?
+    };
+
+    let table: toml::Table = toml::from_str(&content)?;
+    let mut strings = HashMap::new();
+    flatten("", table, &mut strings);
+    Ok(strings)
+}
+
+/// Joins the keys of nested tables with dots, e.g. `[menu] preview` becomes `menu.preview`
+fn flatten(prefix: &str, table: toml::Table, strings: &mut HashMap<String, String>) {
+    for (key, value) in table {
+        let key = if prefix.is_empty() {
+            key
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match value {
+            toml::Value::String(string) => {
+                strings.insert(key, string);
+            }
+            toml::Value::Table(table) => flatten(&key, table, strings),
+            // Only strings are displayed
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::{BUILT_IN, FALLBACK, Language, LanguageError, load_strings};
+
+    #[test]
+    fn built_in_languages_have_every_key() {
+        let nowhere = Path::new("");
+        let fallback = load_strings(nowhere, FALLBACK).unwrap();
+
+        for (name, _) in BUILT_IN {
+            let strings = load_strings(nowhere, name).unwrap();
+            let mut missing = fallback
+                .keys()
+                .filter(|key| !strings.contains_key(*key))
+                .collect::<Vec<_>>();
+            missing.sort();
+            assert!(missing.is_empty(), "'{name}' is missing {missing:?}");
+        }
+    }
+
+    #[test]
+    fn missing_keys_fall_back_to_english() {
+        let directory =
+            std::env::temp_dir().join(format!("octotype-language-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(
+            directory.join("xx.toml"),
+            "[menu]\nmain_menu = \"Menu principal\"\n",
+        )
+        .unwrap();
+
+        let language = Language::load(&directory, "xx").unwrap();
+
+        assert_eq!(language.get("menu.main_menu"), "Menu principal");
+        assert_eq!(language.get("menu.preview"), "Preview");
+        assert_eq!(language.get("menu.unknown"), "menu.unknown");
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn format_fills_in_arguments() {
+        let language = Language::load(Path::new(""), "en").unwrap();
+
+        assert_eq!(
+            language.format("loading.cancel", &[("key", &"esc")]),
+            "<esc> to cancel"
+        );
+        assert!(matches!(
+            Language::load(Path::new(""), "xx"),
+            Err(LanguageError::Unknown(_))
+        ));
+    }
+}
@@ -7,7 +7,10 @@ use derive_more::From;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::config::parameters::{self, ParameterDefinitions, ParameterValues};
+use crate::config::{
+    parameters::{self, ParameterDefinitions, ParameterValues},
+    source::Transform,
+};
 
 #[derive(Debug, From, Error)]
 pub enum ModeError {
@@ -41,6 +44,8 @@ pub fn create_default_modes() -> HashMap<String, ModeConfig> {
             parameters: HashMap::new(),
             conditions: ConditionConfig::default(),
             overrides: HashMap::new(),
+            transforms: Vec::new(),
+            seed: None,
         },
     );
     modes.insert(
@@ -59,6 +64,7 @@ pub fn create_default_modes() -> HashMap<String, ModeConfig> {
                         max: i64::MAX,
                         step: 2,
                         default: Some(30),
+                        depends_on: None,
                         value: 30,
                     },
                 ),
@@ -69,6 +75,7 @@ pub fn create_default_modes() -> HashMap<String, ModeConfig> {
                         max: i64::MAX,
                         step: 5,
                         default: Some(60),
+                        depends_on: None,
                         value: 60,
                     },
                 ),
@@ -81,6 +88,8 @@ pub fn create_default_modes() -> HashMap<String, ModeConfig> {
                 ..Default::default()
             },
             overrides: HashMap::new(),
+            transforms: Vec::new(),
+            seed: None,
         },
     );
     modes.insert(
@@ -97,13 +106,35 @@ pub fn create_default_modes() -> HashMap<String, ModeConfig> {
                 ..Default::default()
             },
             overrides: HashMap::new(),
+            transforms: Vec::new(),
+            seed: None,
+        },
+    );
+    modes.insert(
+        "Quote".to_string(),
+        ModeConfig {
+            meta: ModeMeta {
+                name: "Quote".to_string(),
+                description: "Type about a minute of quotes".to_string(),
+                allowed_sources: Some(vec!["Quotes".to_string()]),
+            },
+            parameters: HashMap::new(),
+            conditions: ConditionConfig::default(),
+            overrides: HashMap::new(),
+            transforms: Vec::new(),
+            seed: None,
         },
     );
 
     modes
 }
 
-pub fn get_modes(from_dir: &PathBuf) -> Result<HashMap<String, ModeConfig>, ModeError> {
+/// Loads all modes from the directory. If it contains none and `bootstrap` is set, the default
+/// modes are written to it first
+pub fn get_modes(
+    from_dir: &PathBuf,
+    bootstrap: bool,
+) -> Result<HashMap<String, ModeConfig>, ModeError> {
     if !from_dir.exists() {
         std::fs::create_dir_all(from_dir)?;
     }
@@ -123,6 +154,9 @@ pub fn get_modes(from_dir: &PathBuf) -> Result<HashMap<String, ModeConfig>, Mode
 
     // If no files found, write defaults and return them
     if !has_files {
+        if !bootstrap {
+            return Ok(HashMap::new());
+        }
         let modes = create_default_modes();
         for (filename, mode) in &modes {
             let mut location = from_dir.clone();
@@ -166,6 +200,11 @@ pub struct ModeConfig {
     pub conditions: ConditionConfig,
     #[serde(default)]
     pub overrides: HashMap<String, HashMap<String, String>>,
+    /// Applied to the text of the sources, after their own transforms
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transforms: Vec<Transform>,
+    /// Makes the text of every session of this mode the same, for the same source and parameters
+    pub seed: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -182,6 +221,17 @@ impl ModeMeta {
     }
 }
 
+/// How the goal conditions (`time`, `words_typed`) of a mode are combined
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConditionCombinator {
+    /// The session ends when any of the goals is reached
+    #[default]
+    Any,
+    /// The session ends when all of the goals are reached
+    All,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ConditionValue {
@@ -199,7 +249,21 @@ pub enum ParseConditionError {
     Number(&'static str, String),
 }
 
+impl ParseConditionError {
+    /// The name of the condition that failed to parse
+    pub const fn condition(&self) -> &'static str {
+        match self {
+            Self::Bool(condition, _) | Self::Number(condition, _) => condition,
+        }
+    }
+}
+
 impl ConditionValue {
+    /// Whether the value uses the parameter called `name`
+    pub fn references(&self, name: &str) -> bool {
+        matches!(self, Self::String(string) if string.contains(&format!("{{{name}}}")))
+    }
+
     pub fn parse_bool(
         self,
         key: &'static str,
@@ -244,6 +308,36 @@ pub struct ConditionConfig {
     pub words_typed: Option<ConditionValue>,
     pub allow_deletions: ConditionValue,
     pub allow_errors: ConditionValue,
+    pub ignore_case: ConditionValue,
+    pub require_fix_before_continue: ConditionValue,
+    pub fix_below_accuracy: ConditionValue,
+    pub require_space: ConditionValue,
+    pub blind_ahead: ConditionValue,
+    pub reveal_on_error: ConditionValue,
+    pub show_speed: ConditionValue,
+    pub feedback_delay: ConditionValue,
+    pub combinator: ConditionCombinator,
+}
+
+impl ConditionConfig {
+    /// The value of the condition called `name`, if it's set
+    pub fn value(&self, name: &str) -> Option<&ConditionValue> {
+        match name {
+            "time" => self.time.as_ref(),
+            "words_typed" => self.words_typed.as_ref(),
+            "allow_deletions" => Some(&self.allow_deletions),
+            "allow_errors" => Some(&self.allow_errors),
+            "ignore_case" => Some(&self.ignore_case),
+            "require_fix_before_continue" => Some(&self.require_fix_before_continue),
+            "fix_below_accuracy" => Some(&self.fix_below_accuracy),
+            "require_space" => Some(&self.require_space),
+            "blind_ahead" => Some(&self.blind_ahead),
+            "reveal_on_error" => Some(&self.reveal_on_error),
+            "show_speed" => Some(&self.show_speed),
+            "feedback_delay" => Some(&self.feedback_delay),
+            _ => None,
+        }
+    }
 }
 
 impl Default for ConditionConfig {
@@ -253,6 +347,15 @@ impl Default for ConditionConfig {
             words_typed: None,
             allow_deletions: ConditionValue::Bool(true),
             allow_errors: ConditionValue::Bool(true),
+            ignore_case: ConditionValue::Bool(false),
+            require_fix_before_continue: ConditionValue::Bool(false),
+            fix_below_accuracy: ConditionValue::Number(100),
+            require_space: ConditionValue::Bool(false),
+            blind_ahead: ConditionValue::Bool(false),
+            reveal_on_error: ConditionValue::Bool(false),
+            show_speed: ConditionValue::Bool(true),
+            feedback_delay: ConditionValue::Number(0),
+            combinator: ConditionCombinator::Any,
         }
     }
 }
@@ -261,7 +364,9 @@ impl Default for ConditionConfig {
 mod test {
     use std::{fs::read_to_string, path::PathBuf, str::FromStr};
 
-    use crate::config::ModeConfig;
+    use crate::config::{ModeConfig, parameters::ParameterValues};
+
+    use super::{ConditionConfig, ConditionValue};
 
     #[test]
     fn parse_official_modes() {
@@ -280,4 +385,24 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn failing_conditions_lead_to_their_parameter() {
+        let conditions = ConditionConfig {
+            time: Some(ConditionValue::String("{time}".to_string())),
+            ..ConditionConfig::default()
+        };
+        // `{time}` doesn't resolve without the parameter
+        let error = conditions
+            .time
+            .clone()
+            .unwrap()
+            .parse_number("time", &std::iter::empty().collect::<ParameterValues>())
+            .unwrap_err();
+
+        let value = conditions.value(error.condition()).unwrap();
+        assert!(value.references("time"));
+        assert!(!value.references("words"));
+        assert!(!conditions.value("allow_errors").unwrap().references("time"));
+    }
 }
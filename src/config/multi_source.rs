@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// Settings for sessions combining multiple sources
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct MultiSourceConfig {
+    pub order: SourceOrder,
+    /// Skip sources that fail, instead of failing the whole session. The session still fails if
+    /// every source does
+    pub skip_failed: bool,
+}
+
+/// How the texts of combined sources are joined
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceOrder {
+    /// The text of each source, one after another
+    #[default]
+    Sequential,
+    /// One word from each source at a time
+    Interleaved,
+}
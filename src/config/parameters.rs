@@ -24,6 +24,9 @@ pub enum ParameterError {
 
     #[error("Default doesn't exist in selection")]
     DefaultNonExistant,
+
+    #[error("Invalid dependency '{0}' - Expected '<parameter>=<value>'")]
+    InvalidDependency(String),
 }
 
 pub struct ParameterValues(HashMap<String, Parameter>);
@@ -33,6 +36,17 @@ impl ParameterValues {
         self.0.get(key)
     }
 
+    /// Makes `seed` available as the `{seed}` parameter, unless a parameter of that name exists
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.0
+            .entry("seed".to_string())
+            .or_insert_with(|| Parameter {
+                definition: Definition::FixedString(seed.to_string()),
+                mutable: false,
+            });
+        self
+    }
+
     pub fn replace_values(&self, string: &str) -> String {
         let mut result = String::new();
         let mut remaining = string;
@@ -79,6 +93,16 @@ pub struct Parameter {
 }
 
 impl Parameter {
+    /// The parameter that has to have a certain value for this one to have an effect
+    pub const fn dependency(&self) -> Option<&Dependency> {
+        match &self.definition {
+            Definition::Range { depends_on, .. }
+            | Definition::Selection { depends_on, .. }
+            | Definition::Text { depends_on, .. } => depends_on.as_ref(),
+            _ => None,
+        }
+    }
+
     pub const fn is_mutable(&self) -> bool {
         if self.mutable {
             self.definition.is_mutable()
@@ -94,11 +118,35 @@ impl Parameter {
                 options, selected, ..
             } => options[*selected].clone(),
             Definition::Toggle(b) => b.to_string(),
+            Definition::Text { value, .. } => value.clone(),
             Definition::FixedNumber(num) => num.to_string(),
             Definition::FixedString(s) => s.to_string(),
         }
     }
 
+    /// Whether the value is edited by typing, instead of incrementing and decrementing it
+    pub const fn is_text(&self) -> bool {
+        self.is_mutable() && matches!(self.definition, Definition::Text { .. })
+    }
+
+    /// Appends `character` to a text value
+    pub fn push(&mut self, character: char) {
+        if self.is_mutable()
+            && let Definition::Text { value, .. } = &mut self.definition
+        {
+            value.push(character);
+        }
+    }
+
+    /// Removes the last character of a text value
+    pub fn pop(&mut self) {
+        if self.is_mutable()
+            && let Definition::Text { value, .. } = &mut self.definition
+        {
+            value.pop();
+        }
+    }
+
     pub fn increment(&mut self) {
         if !self.is_mutable() {
             return;
@@ -123,6 +171,8 @@ impl Parameter {
                 }
             }
             Definition::Toggle(b) => *b = !*b,
+            // Edited by typing instead
+            Definition::Text { .. } => (),
             _ => unreachable!("Tried to modify a non-mutable definition"),
         }
     }
@@ -145,6 +195,8 @@ impl Parameter {
                 options, selected, ..
             } => *selected = (*selected + 1) % options.len(),
             Definition::Toggle(b) => *b = !*b,
+            // Edited by typing instead
+            Definition::Text { .. } => (),
             _ => unreachable!("Tried to modify a non-mutable definition"),
         }
     }
@@ -161,15 +213,26 @@ pub enum Definition {
         #[serde(default = "default_range_step")]
         step: i64,
         default: Option<i64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        depends_on: Option<Dependency>,
         #[serde(skip)]
         value: i64,
     },
     Selection {
         options: Vec<String>,
         default: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        depends_on: Option<Dependency>,
         #[serde(skip)]
         selected: usize,
     },
+    Text {
+        default: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        depends_on: Option<Dependency>,
+        #[serde(skip)]
+        value: String,
+    },
     Toggle(bool),
     FixedNumber(i64),
     FixedString(String),
@@ -206,6 +269,7 @@ impl Definition {
                 options,
                 default,
                 selected,
+                ..
             } => {
                 if let Some(d) = default
                     && let Some(select) = options.iter().position(|opt| opt == d)
@@ -215,6 +279,7 @@ impl Definition {
                     *selected = 0;
                 }
             }
+            Self::Text { default, value, .. } => value.clone_from(default),
             _ => (),
         })
     }
@@ -272,6 +337,35 @@ impl Definition {
     }
 }
 
+/// A parameter only has an effect if another parameter has a certain value. Written as
+/// `"<parameter>=<value>"`, e.g. `"mode=length"`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Dependency {
+    pub parameter: String,
+    pub value: String,
+}
+
+impl TryFrom<String> for Dependency {
+    type Error = ParameterError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.split_once('=') {
+            Some((parameter, expected)) if !parameter.trim().is_empty() => Ok(Self {
+                parameter: parameter.trim().to_string(),
+                value: expected.trim().to_string(),
+            }),
+            _ => Err(ParameterError::InvalidDependency(value)),
+        }
+    }
+}
+
+impl From<Dependency> for String {
+    fn from(value: Dependency) -> Self {
+        format!("{}={}", value.parameter, value.value)
+    }
+}
+
 pub const fn default_range_step() -> i64 {
     1
 }
@@ -279,3 +373,77 @@ pub const fn default_range_step() -> i64 {
 pub const fn default_range_max() -> i64 {
     i64::MAX
 }
+
+#[cfg(test)]
+mod test {
+    use serde::Deserialize;
+
+    use super::{Definition, Dependency, ParameterDefinitions};
+
+    #[derive(Deserialize)]
+    struct Parameters {
+        parameters: ParameterDefinitions,
+    }
+
+    #[test]
+    fn text_parameter_is_typed() {
+        let Parameters { parameters } = toml::from_str(
+            r#"
+            [parameters]
+            query = { default = "rust" }
+            count = { min = 1, default = 5 }
+            "#,
+        )
+        .unwrap();
+
+        assert!(matches!(parameters["count"], Definition::Range { .. }));
+        let mut query = parameters["query"].clone().into_parameter(true).unwrap();
+        assert!(query.is_text());
+        assert_eq!(query.get_value(), "rust");
+
+        query.pop();
+        query.push('t');
+        query.increment();
+        assert_eq!(query.get_value(), "rust");
+
+        let mut fixed = parameters["query"].clone().into_parameter(false).unwrap();
+        assert!(!fixed.is_text());
+        fixed.push('!');
+        assert_eq!(fixed.get_value(), "rust");
+    }
+
+    #[test]
+    fn dependency_is_parsed() {
+        let Parameters { parameters } = toml::from_str(
+            r#"
+            [parameters]
+            mode = { options = ["words", "length"] }
+            max_length = { min = 1, depends_on = "mode = length" }
+            "#,
+        )
+        .unwrap();
+
+        let max_length = parameters["max_length"]
+            .clone()
+            .into_parameter(true)
+            .unwrap();
+        assert_eq!(
+            max_length.dependency(),
+            Some(&Dependency {
+                parameter: "mode".to_string(),
+                value: "length".to_string(),
+            })
+        );
+        assert_eq!(
+            parameters["mode"]
+                .clone()
+                .into_parameter(true)
+                .unwrap()
+                .dependency(),
+            None
+        );
+
+        assert!(Dependency::try_from("mode".to_string()).is_err());
+        assert!(Dependency::try_from("=length".to_string()).is_err());
+    }
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// Settings for the session view
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SessionConfig {
+    /// The gauges below the text, from top to bottom
+    pub gauges: Vec<GaugeKind>,
+    /// Shows only the text, without the top bar, gauges and mistakes
+    pub minimal: bool,
+    /// Shown where the text has a line break, which has to be typed with enter
+    pub newline_glyph: Option<char>,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            gauges: vec![GaugeKind::Time, GaugeKind::Words],
+            minimal: false,
+            newline_glyph: None,
+        }
+    }
+}
+
+/// A gauge below the text of a session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GaugeKind {
+    /// The live Wpm
+    Wpm,
+    /// The live accuracy
+    Accuracy,
+    /// The elapsed time, if the mode has a time limit
+    Time,
+    /// The typed words, if the mode has a word goal
+    Words,
+}
@@ -4,7 +4,14 @@ use derive_more::From;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::config::parameters::ParameterDefinitions;
+use crate::config::{
+    mode::ConditionValue,
+    parameters::{self, ParameterDefinitions},
+};
+
+pub use transform::Transform;
+
+pub mod transform;
 
 // Default config helpers
 const BROWNFOX_TEXT: &str = "The quick brown fox jumps over the lazy dog, testing my typing speed with every leap, but I'll soon catch up.";
@@ -13,6 +20,26 @@ const NUMBER_WORDS: [&str; 20] = [
     "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety", "hundred",
 ];
 
+const QUOTES: [&str; 12] = [
+    "It was the best of times, it was the worst of times, it was the age of wisdom, it was the age of foolishness.",
+    "It is a truth universally acknowledged, that a single man in possession of a good fortune, must be in want of a wife.",
+    "All happy families are alike; each unhappy family is unhappy in its own way.",
+    "Two roads diverged in a wood, and I took the one less traveled by, and that has made all the difference.",
+    "The only thing we have to fear is fear itself.",
+    "It does not matter how slowly you go as long as you do not stop.",
+    "We are what we repeatedly do. Excellence, then, is not an act, but a habit.",
+    "The journey of a thousand miles begins with a single step.",
+    "Whether you think you can, or you think you can't, you're right.",
+    "I have not failed. I've just found 10,000 ways that won't work.",
+    "Four score and seven years ago our fathers brought forth on this continent, a new nation, conceived in Liberty, and dedicated to the proposition that all men are created equal.",
+    "Call me Ishmael. Some years ago, never mind how long precisely, having little or no money in my purse, and nothing particular to interest me on shore, I thought I would sail about a little and see the watery part of the world.",
+];
+/// Length of the text of the built-in quotes source, about a minute of typing
+const QUOTE_LENGTH: usize = 300;
+
+/// The most common English words, most frequent first
+pub const COMMON_WORDS: &str = include_str!("source/common_words.txt");
+
 #[derive(Debug, From, Error)]
 pub enum SourceError {
     #[error("Failed to read sources directory '{directory}': {error}")]
@@ -51,6 +78,7 @@ pub fn create_default_sources() -> HashMap<String, SourceConfig> {
                 randomize: false,
             },
             parameters: HashMap::new(),
+            transforms: Vec::new(),
         },
     );
     sources.insert(
@@ -65,13 +93,74 @@ pub fn create_default_sources() -> HashMap<String, SourceConfig> {
                 randomize: true,
             },
             parameters: HashMap::new(),
+            transforms: Vec::new(),
+        },
+    );
+    sources.insert(
+        "quotes".to_string(),
+        SourceConfig {
+            meta: SourceMeta {
+                name: "Quotes".to_string(),
+                description: "Well-known quotes, with punctuation".to_string(),
+            },
+            generator: GeneratorDefinition::List {
+                source: ListSource::Array(QUOTES.into_iter().map(str::to_string).collect()),
+                randomize: true,
+            },
+            parameters: HashMap::new(),
+            transforms: vec![Transform::TruncateToChars(QUOTE_LENGTH)],
+        },
+    );
+    sources.insert(
+        "common_words".to_string(),
+        SourceConfig {
+            meta: SourceMeta {
+                name: "CommonWords".to_string(),
+                description: "The most common English words".to_string(),
+            },
+            generator: GeneratorDefinition::CommonWords {
+                frequency_rank_max: ConditionValue::String("{frequency_rank_max}".to_string()),
+                words: ConditionValue::String("{words}".to_string()),
+            },
+            parameters: [
+                (
+                    "frequency_rank_max".to_string(),
+                    parameters::Definition::Range {
+                        min: 10,
+                        max: COMMON_WORDS.split_whitespace().count() as i64,
+                        step: 10,
+                        default: Some(100),
+                        depends_on: None,
+                        value: 100,
+                    },
+                ),
+                (
+                    "words".to_string(),
+                    parameters::Definition::Range {
+                        min: 10,
+                        max: i64::MAX,
+                        step: 10,
+                        default: Some(50),
+                        depends_on: None,
+                        value: 50,
+                    },
+                ),
+            ]
+            .into_iter()
+            .collect(),
+            transforms: Vec::new(),
         },
     );
 
     sources
 }
 
-pub fn get_sources(from_dir: &PathBuf) -> Result<HashMap<String, SourceConfig>, SourceError> {
+/// Loads all sources from the directory. If it contains none and `bootstrap` is set, the default
+/// sources are written to it first
+pub fn get_sources(
+    from_dir: &PathBuf,
+    bootstrap: bool,
+) -> Result<HashMap<String, SourceConfig>, SourceError> {
     if !from_dir.exists() {
         std::fs::create_dir_all(from_dir)?;
     }
@@ -91,6 +180,9 @@ pub fn get_sources(from_dir: &PathBuf) -> Result<HashMap<String, SourceConfig>,
 
     // If no files found, write defaults and return them
     if !has_files {
+        if !bootstrap {
+            return Ok(HashMap::new());
+        }
         let sources = create_default_sources();
         for (filename, source) in &sources {
             let mut location = from_dir.clone();
@@ -131,6 +223,8 @@ pub struct SourceConfig {
     #[serde(default)]
     pub parameters: ParameterDefinitions,
     pub generator: GeneratorDefinition,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transforms: Vec<Transform>,
 }
 
 impl SourceConfig {
@@ -162,6 +256,13 @@ pub enum GeneratorDefinition {
         source: ListSource,
         randomize: bool,
     },
+    /// Samples from the bundled [`COMMON_WORDS`], favoring the more frequent words
+    CommonWords {
+        /// Only words up to this rank are used, e.g. `100` for the 100 most common words
+        frequency_rank_max: ConditionValue,
+        /// The amount of words to generate
+        words: ConditionValue,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -192,6 +293,7 @@ pub enum Formatting {
 mod test {
     use std::{fs::read_to_string, path::PathBuf, str::FromStr};
 
+    use super::{create_default_sources, get_sources};
     use crate::config::SourceConfig;
 
     #[test]
@@ -211,4 +313,23 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn bootstrap_writes_default_sources() {
+        let directory =
+            std::env::temp_dir().join(format!("octotype-bootstrap-{}", std::process::id()));
+
+        let sources = get_sources(&directory, false).unwrap();
+        assert!(sources.is_empty());
+        assert_eq!(directory.read_dir().unwrap().count(), 0);
+
+        let sources = get_sources(&directory, true).unwrap();
+        assert_eq!(sources.len(), create_default_sources().len());
+
+        // The written files are loaded on the next start
+        let sources = get_sources(&directory, false).unwrap();
+        assert_eq!(sources.len(), create_default_sources().len());
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
 }
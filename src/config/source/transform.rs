@@ -0,0 +1,366 @@
+use std::collections::HashMap;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{
+    mode::{ConditionValue, ParseConditionError},
+    parameters::ParameterValues,
+};
+
+/// Characters of the number row (digits and their shifted symbols)
+const NUMBER_ROW: [char; 24] = [
+    '1', '2', '3', '4', '5', '6', '7', '8', '9', '0', '!', '@', '#', '$', '%', '^', '&', '*', '(',
+    ')', '-', '_', '=', '+',
+];
+
+/// Marks attached to the end of a word
+const PUNCTUATION: [&str; 6] = [",", ".", "!", "?", ";", ":"];
+/// Pairs of marks put around a word
+const ENCLOSING_PUNCTUATION: [(&str, &str); 2] = [("\"", "\""), ("(", ")")];
+
+/// A post-processing step applied to the text fetched from a source.
+///
+/// Transforms are applied in the order they are listed, each one receiving the output of the
+/// previous one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Transform {
+    /// Replaces every character of each word with a random digit or symbol of the number row,
+    /// keeping the length of the words intact.
+    DigitsAndSymbols,
+    /// Removes every character that isn't alphanumeric from each word. Words that end up empty
+    /// are dropped, and the remaining words are joined by single spaces.
+    StripPunctuation,
+    /// Capitalizes the first letter of the text and of every sentence following a `.`, `!` or
+    /// `?`. Operates on the whole (formatted) text, so line breaks and spacing are preserved.
+    SentenceCase,
+    /// Shuffles the words, biased towards words containing the given (weak) characters. Each
+    /// character is weighted by its count, e.g. the amount of times it was mistyped.
+    ///
+    /// Words without any of the characters are kept, but tend to end up last.
+    WeaknessWeighted(HashMap<char, usize>),
+    /// Cuts the text down to at most the given amount of characters. The text is cut at the last
+    /// whitespace before the limit, so words are kept whole unless the first one is too long.
+    TruncateToChars(usize),
+    /// Attaches punctuation to a share of the words, and replaces another share with numbers.
+    /// Both shares are given in percent of the words, and may be replacements.
+    ///
+    /// Like [`Transform::StripPunctuation`], the words are joined by single spaces.
+    PunctuationAndNumbers {
+        punctuation_ratio: ConditionValue,
+        numbers_ratio: ConditionValue,
+    },
+}
+
+impl Transform {
+    /// Replaces the replacements in the transform with their values from `parameters`
+    pub fn resolve(self, parameters: &ParameterValues) -> Result<Self, ParseConditionError> {
+        match self {
+            Self::PunctuationAndNumbers {
+                punctuation_ratio,
+                numbers_ratio,
+            } => Ok(Self::PunctuationAndNumbers {
+                punctuation_ratio: ConditionValue::Number(
+                    punctuation_ratio.parse_number("punctuation_ratio", parameters)?,
+                ),
+                numbers_ratio: ConditionValue::Number(
+                    numbers_ratio.parse_number("numbers_ratio", parameters)?,
+                ),
+            }),
+            other => Ok(other),
+        }
+    }
+
+    pub fn apply<R: Rng + ?Sized>(&self, text: String, rng: &mut R) -> String {
+        match self {
+            Self::DigitsAndSymbols => text
+                .chars()
+                .map(|character| {
+                    if character.is_whitespace() {
+                        character
+                    } else {
+                        NUMBER_ROW[rng.random_range(..NUMBER_ROW.len())]
+                    }
+                })
+                .collect(),
+            Self::StripPunctuation => text
+                .split_whitespace()
+                .map(|word| {
+                    word.chars()
+                        .filter(|c| c.is_alphanumeric())
+                        .collect::<String>()
+                })
+                .filter(|word| !word.is_empty())
+                .collect::<Vec<_>>()
+                .join(" "),
+            Self::SentenceCase => {
+                let mut result = String::with_capacity(text.len());
+                let mut capitalize = true;
+                let mut after_terminator = false;
+
+                for character in text.chars() {
+                    if character.is_alphanumeric() {
+                        if capitalize {
+                            result.extend(character.to_uppercase());
+                        } else {
+                            result.push(character);
+                        }
+                        capitalize = false;
+                        after_terminator = false;
+                        continue;
+                    }
+
+                    if matches!(character, '.' | '!' | '?') {
+                        after_terminator = true;
+                    } else if character.is_whitespace() && after_terminator {
+                        capitalize = true;
+                    } else if !character.is_whitespace() {
+                        after_terminator = false;
+                    }
+                    result.push(character);
+                }
+
+                result
+            }
+            Self::WeaknessWeighted(weakness) => {
+                // Weighted shuffle (Efraimidis-Spirakis): Every word draws the key `u^(1/w)`, and
+                // words are ordered by their key, highest first
+                let mut keyed = text
+                    .split_whitespace()
+                    .map(|word| {
+                        let score = word
+                            .chars()
+                            .map(|c| weakness.get(&c).copied().unwrap_or(0))
+                            .sum::<usize>();
+                        let weight = 1.0 + score as f64;
+                        (rng.random::<f64>().powf(weight.recip()), word)
+                    })
+                    .collect::<Vec<_>>();
+
+                keyed.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+                keyed
+                    .into_iter()
+                    .map(|(_, word)| word)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            }
+            Self::TruncateToChars(max) => {
+                // Byte index of the first character past the limit
+                let Some((limit, next)) = text.char_indices().nth(*max) else {
+                    return text;
+                };
+
+                let end = if next.is_whitespace() {
+                    limit
+                } else {
+                    text[..limit].rfind(char::is_whitespace).unwrap_or(limit)
+                };
+
+                text[..end].trim_end().to_string()
+            }
+            Self::PunctuationAndNumbers {
+                punctuation_ratio,
+                numbers_ratio,
+            } => {
+                // A single roll per word keeps both shares exact, e.g. 20% and 10% leave 70% of
+                // the words as they are
+                let numbers = fraction(numbers_ratio);
+                let punctuation = numbers + fraction(punctuation_ratio);
+
+                text.split_whitespace()
+                    .map(|word| {
+                        let roll = rng.random::<f64>();
+                        if roll < numbers {
+                            rng.random_range(0..10_000).to_string()
+                        } else if roll < punctuation {
+                            punctuate(word, rng)
+                        } else {
+                            word.to_string()
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            }
+        }
+    }
+}
+
+/// A share in percent as a fraction. Replacements are resolved by [`Transform::resolve`] before
+/// the transform is applied
+fn fraction(percent: &ConditionValue) -> f64 {
+    match percent {
+        ConditionValue::Number(percent) => (*percent).min(100) as f64 / 100.0,
+        ConditionValue::String(_) | ConditionValue::Bool(_) => 0.0,
+    }
+}
+
+/// Attaches a random punctuation mark to `word`, or puts a pair of them around it
+fn punctuate<R: Rng + ?Sized>(word: &str, rng: &mut R) -> String {
+    let choice = rng.random_range(..PUNCTUATION.len() + ENCLOSING_PUNCTUATION.len());
+    PUNCTUATION.get(choice).map_or_else(
+        || {
+            let (open, close) = ENCLOSING_PUNCTUATION[choice - PUNCTUATION.len()];
+            format!("{open}{word}{close}")
+        },
+        |mark| format!("{word}{mark}"),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use std::collections::HashMap;
+
+    use super::{NUMBER_ROW, Transform};
+    use crate::config::{
+        mode::ConditionValue,
+        parameters::{Definition, ParameterValues},
+    };
+
+    #[test]
+    fn digits_and_symbols_only_outputs_number_row() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let text = "the quick brown fox jumps over the lazy dog".to_string();
+
+        let output = Transform::DigitsAndSymbols.apply(text.clone(), &mut rng);
+
+        assert!(output.chars().all(|c| c == ' ' || NUMBER_ROW.contains(&c)));
+        let lengths = |s: &str| s.split(' ').map(str::len).collect::<Vec<_>>();
+        assert_eq!(lengths(&text), lengths(&output));
+    }
+
+    #[test]
+    fn strip_punctuation_removes_symbols_and_empty_words() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let output = Transform::StripPunctuation.apply("hello, world!".to_string(), &mut rng);
+        assert_eq!(
+            output.split(' ').collect::<Vec<_>>(),
+            vec!["hello", "world"]
+        );
+
+        let output = Transform::StripPunctuation.apply("a -- b ...".to_string(), &mut rng);
+        assert_eq!(output, "a b");
+    }
+
+    #[test]
+    fn sentence_case_capitalizes_sentences() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let output = Transform::SentenceCase.apply(
+            "the quick brown fox. jumps over!  the lazy dog?\n\"yes\" it did".to_string(),
+            &mut rng,
+        );
+
+        assert_eq!(
+            output,
+            "The quick brown fox. Jumps over!  The lazy dog?\n\"Yes\" it did"
+        );
+    }
+
+    #[test]
+    fn sentence_case_ignores_inner_periods() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let output = Transform::SentenceCase.apply("version 1.5 is out".to_string(), &mut rng);
+
+        assert_eq!(output, "Version 1.5 is out");
+    }
+
+    #[test]
+    fn weakness_weighted_favors_weak_characters() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let transform = Transform::WeaknessWeighted(HashMap::from([('z', 5)]));
+        let text = "apple zebra lemon fuzz grape melon".to_string();
+
+        let mut z_first = 0;
+        for _ in 0..1000 {
+            let output = transform.apply(text.clone(), &mut rng);
+
+            let mut words = output.split(' ').collect::<Vec<_>>();
+            if words[0].contains('z') {
+                z_first += 1;
+            }
+
+            // No words are lost or duplicated
+            words.sort_unstable();
+            assert_eq!(
+                words,
+                vec!["apple", "fuzz", "grape", "lemon", "melon", "zebra"]
+            );
+        }
+
+        // Uniform shuffling would put a 'z' word first a third of the time
+        assert!(z_first > 700, "'z' words were first {z_first}/1000 times");
+    }
+
+    #[test]
+    fn weakness_weighted_parses_from_toml() {
+        #[derive(serde::Deserialize)]
+        struct Wrapper {
+            transforms: Vec<Transform>,
+        }
+
+        let wrapper: Wrapper = toml::from_str(
+            r#"transforms = ["strip_punctuation", { weakness_weighted = { z = 5, q = 2 } }]"#,
+        )
+        .unwrap();
+
+        let Transform::WeaknessWeighted(weakness) = &wrapper.transforms[1] else {
+            panic!(
+                "Expected weakness_weighted, got {:?}",
+                wrapper.transforms[1]
+            );
+        };
+        assert_eq!(weakness, &HashMap::from([('z', 5), ('q', 2)]));
+    }
+
+    #[test]
+    fn truncate_to_chars_keeps_words_whole() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut truncate = |text: &str, max: usize| {
+            Transform::TruncateToChars(max).apply(text.to_string(), &mut rng)
+        };
+
+        assert_eq!(truncate("The quick brown fox.", 9), "The quick");
+        assert_eq!(truncate("The quick brown fox.", 12), "The quick");
+        assert_eq!(truncate("The quick brown fox.", 20), "The quick brown fox.");
+        assert_eq!(
+            truncate("The quick brown fox.", 100),
+            "The quick brown fox."
+        );
+        assert_eq!(truncate("The quick brown fox.", 2), "Th");
+        assert_eq!(truncate("ab\ncd ef", 4), "ab");
+    }
+
+    #[test]
+    fn punctuation_and_numbers_match_their_ratios() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let transform = Transform::PunctuationAndNumbers {
+            punctuation_ratio: ConditionValue::String("{punctuation}".to_string()),
+            numbers_ratio: ConditionValue::Number(10),
+        };
+        let parameters = std::iter::once((
+            "punctuation".to_string(),
+            Definition::FixedNumber(30).into_parameter(false).unwrap(),
+        ))
+        .collect::<ParameterValues>();
+        let transform = transform.resolve(&parameters).unwrap();
+
+        let text = vec!["word"; 10_000].join(" ");
+        let output = transform.apply(text, &mut rng);
+        let words = output.split(' ').collect::<Vec<_>>();
+        assert_eq!(words.len(), 10_000);
+
+        let share = |predicate: fn(&str) -> bool| {
+            words.iter().filter(|word| predicate(word)).count() as f64 / words.len() as f64
+        };
+        let numbers = share(|word| word.chars().all(|c| c.is_ascii_digit()));
+        let punctuated = share(|word| word.contains("word") && word != "word");
+        assert!((numbers - 0.1).abs() < 0.02, "{numbers}");
+        assert!((punctuated - 0.3).abs() < 0.02, "{punctuated}");
+    }
+}
@@ -2,11 +2,18 @@ use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct StatisticsConfig {
     pub save_enabled: bool,
     pub history_limit: usize,
     pub directory: Option<PathBuf>,
+    pub min_accuracy_to_save: f64,
+    pub min_characters_to_save: usize,
+    /// In seconds
+    pub min_duration_to_save: f64,
+    pub trend_smoothing_window: usize,
+    /// Saves which characters were mistyped along with the metrics
+    pub store_input_history: bool,
 }
 
 impl Default for StatisticsConfig {
@@ -15,6 +22,11 @@ impl Default for StatisticsConfig {
             save_enabled: true,
             history_limit: 10,
             directory: None,
+            min_accuracy_to_save: 0.0,
+            min_characters_to_save: 10,
+            min_duration_to_save: 0.0,
+            trend_smoothing_window: 5,
+            store_input_history: true,
         }
     }
 }
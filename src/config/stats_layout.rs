@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+/// Settings for the statistics page shown after a session
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct StatsLayoutConfig {
+    /// The text panels on the left, from top to bottom
+    pub panels: Vec<StatsPanel>,
+    /// The charts on the right, from top to bottom
+    pub charts: Vec<StatsChart>,
+    /// Width of the panels in percent. The charts take the rest
+    pub panels_width: u16,
+}
+
+impl Default for StatsLayoutConfig {
+    fn default() -> Self {
+        Self {
+            panels: vec![
+                StatsPanel::Summary,
+                StatsPanel::FailedCharacters,
+                StatsPanel::SlowestWords,
+            ],
+            charts: vec![StatsChart::Wpm, StatsChart::Accuracy, StatsChart::Latency],
+            panels_width: 30,
+        }
+    }
+}
+
+/// A text panel of the statistics page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsPanel {
+    /// Wpm, accuracy, errors and the like
+    Summary,
+    /// The mistyped characters, by amount of errors
+    FailedCharacters,
+    /// The words that took the longest. Hidden in modes that hide the speed
+    SlowestWords,
+}
+
+/// A chart of the statistics page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsChart {
+    /// Wpm over time. Hidden in modes that hide the speed
+    Wpm,
+    /// Accuracy, consistency and errors over time
+    Accuracy,
+    /// How many keystrokes followed the previous one after how long. Hidden in modes that hide
+    /// the speed
+    Latency,
+}
@@ -1,7 +1,9 @@
 use std::time::{Duration, Instant};
 
+use crossterm::cursor::SetCursorStyle;
+use gladius::State;
 use ratatui::{
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     symbols::Marker,
     text::Span,
 };
@@ -11,8 +13,10 @@ use terminal_colorsaurus::QueryOptions;
 const DEFAULT_SPINNER: [char; 8] = ['⣷', '⣯', '⣟', '⡿', '⢿', '⣻', '⣽', '⣾'];
 
 /// General theme
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Theme {
+    #[serde(default)]
+    pub preset: ThemePreset,
     #[serde(default)]
     pub spinner: Spinner,
     #[serde(default)]
@@ -40,6 +44,7 @@ impl Default for Theme {
         };
 
         Self {
+            preset: ThemePreset::default(),
             spinner: Spinner::default(),
             text: TextTheme::default(),
             plot: PlotTheme::default(),
@@ -50,8 +55,59 @@ impl Default for Theme {
     }
 }
 
+impl Theme {
+    /// Applies the colors of the selected preset, overriding the configured text colors
+    pub const fn apply_preset(&mut self) {
+        match self.preset {
+            ThemePreset::Default => (),
+            ThemePreset::Monochrome => {
+                self.text.success = self.term_fg;
+                self.text.warning = self.term_fg;
+                self.text.error = self.term_fg;
+                self.text.highlight = self.term_fg;
+            }
+            ThemePreset::HighContrast => {
+                self.text.success = Color::LightGreen;
+                self.text.warning = Color::LightYellow;
+                self.text.error = Color::LightRed;
+                self.text.highlight = Color::LightCyan;
+            }
+        }
+    }
+}
+
+/// Built-in accessibility presets, which distinguish character states by style rather than by
+/// hue alone
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemePreset {
+    /// Only the configured colors are used
+    #[default]
+    Default,
+    /// No colors - States are told apart by dimming, italics and reversed text
+    Monochrome,
+    /// Bright colors, with wrong characters underlined and corrected ones in italics
+    HighContrast,
+}
+
+impl ThemePreset {
+    /// Returns the modifiers to add for a character in the given state
+    pub const fn modifiers(self, state: &State) -> Modifier {
+        match (self, state) {
+            (Self::Default, _) => Modifier::empty(),
+            (Self::Monochrome, State::Wrong) => Modifier::REVERSED,
+            (Self::Monochrome, State::Corrected) => Modifier::ITALIC,
+            (Self::Monochrome, State::Correct) => Modifier::empty(),
+            (Self::Monochrome, _) => Modifier::DIM,
+            (Self::HighContrast, State::Wrong) => Modifier::UNDERLINED,
+            (Self::HighContrast, State::Corrected) => Modifier::ITALIC,
+            (Self::HighContrast, _) => Modifier::empty(),
+        }
+    }
+}
+
 /// Spinner logic inspired from: https://crates.io/crates/throbber-widgets-tui
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default)]
 pub struct Spinner {
     pub color: Color,
@@ -102,9 +158,10 @@ impl Spinner {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default)]
 pub struct CursorTheme {
+    pub style: CaretStyle,
     pub color: Color,
     pub text: Color,
 }
@@ -112,14 +169,44 @@ pub struct CursorTheme {
 impl Default for CursorTheme {
     fn default() -> Self {
         Self {
+            style: CaretStyle::default(),
             color: Color::White,
             text: Color::Black,
         }
     }
 }
 
+/// How the caret is shown during a session
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaretStyle {
+    /// The cursor of the terminal, in whatever shape the user set up
+    #[default]
+    Terminal,
+    Bar,
+    Block,
+    Underline,
+    /// The terminal cursor is hidden, and the character under the caret is drawn in the cursor
+    /// colors instead
+    Cell,
+}
+
+impl CaretStyle {
+    /// The shape of the terminal cursor, or `None` if it's left as it is or the caret is drawn as
+    /// a cell
+    pub const fn terminal_style(self) -> Option<SetCursorStyle> {
+        match self {
+            Self::Terminal => None,
+            Self::Bar => Some(SetCursorStyle::SteadyBar),
+            Self::Block => Some(SetCursorStyle::SteadyBlock),
+            Self::Underline => Some(SetCursorStyle::SteadyUnderScore),
+            Self::Cell => None,
+        }
+    }
+}
+
 /// Text color theme
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default)]
 pub struct TextTheme {
     pub success: Color,
@@ -165,7 +252,7 @@ impl PlotSymbol {
 }
 
 /// Plot color and symbol theme
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default)]
 pub struct PlotTheme {
     pub raw_wpm: Color,
@@ -0,0 +1,142 @@
+use std::time::{Duration, Instant};
+
+use discord_rich_presence::{
+    DiscordIpc, DiscordIpcClient,
+    activity::{Activity, Timestamps},
+};
+use web_time::SystemTime;
+
+use crate::{config::Config, page::Page};
+
+/// Minimum time between two presence updates, as Discord rate-limits them
+const UPDATE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// What is shown in the presence
+#[derive(Debug, PartialEq)]
+struct PresenceState {
+    details: String,
+    state: Option<String>,
+    /// Unix time (in milliseconds) of when the session started
+    started: Option<i64>,
+}
+
+/// Discord Rich Presence, showing the current activity.
+///
+/// If Discord isn't running (or the presence is disabled), updates are silently skipped.
+pub struct Presence {
+    client: Option<DiscordIpcClient>,
+    last_update: Option<Instant>,
+    last_state: Option<PresenceState>,
+}
+
+impl Presence {
+    /// Whether the presence is enabled, but can't be shown as no `client_id` is set
+    pub fn is_missing_client_id(config: &Config) -> bool {
+        let settings = &config.settings.discord;
+        settings.enabled && settings.client_id.is_none()
+    }
+
+    pub fn new(config: &Config) -> Self {
+        let settings = &config.settings.discord;
+        let client = settings
+            .client_id
+            .as_ref()
+            .filter(|_| settings.enabled)
+            .and_then(|client_id| {
+                let mut client = DiscordIpcClient::new(client_id);
+                client.connect().ok().map(|()| client)
+            });
+
+        Self {
+            client,
+            last_update: None,
+            last_state: None,
+        }
+    }
+
+    /// Updates the presence from the current page, at most once per [`UPDATE_INTERVAL`]
+    pub fn update(&mut self, page: &Page, config: &Config) {
+        let Some(client) = &mut self.client else {
+            return;
+        };
+
+        if self
+            .last_update
+            .is_some_and(|last| last.elapsed() < UPDATE_INTERVAL)
+        {
+            return;
+        }
+        self.last_update = Some(Instant::now());
+
+        let state = presence_state(page, config);
+        if self.last_state.as_ref() == Some(&state) {
+            return;
+        }
+
+        let mut activity = Activity::new().details(&state.details);
+        if let Some(text) = &state.state {
+            activity = activity.state(text);
+        }
+        if let Some(started) = state.started {
+            activity = activity.timestamps(Timestamps::new().start(started));
+        }
+
+        // Discord might have been closed - The presence is a nicety, so we just stop updating it
+        if client.set_activity(activity).is_err() {
+            self.client = None;
+            return;
+        }
+
+        self.last_state = Some(state);
+    }
+}
+
+impl Drop for Presence {
+    fn drop(&mut self) {
+        if let Some(client) = &mut self.client {
+            let _ = client.close();
+        }
+    }
+}
+
+fn presence_state(page: &Page, config: &Config) -> PresenceState {
+    let settings = &config.settings.discord;
+
+    let details = match page {
+        Page::Session(session) => {
+            let (mode_name, wpm, elapsed) = session.presence();
+
+            let details = if settings.show_mode {
+                format!("Typing - {mode_name}")
+            } else {
+                "Typing".to_string()
+            };
+
+            let started = settings.show_time.then(|| {
+                let started = SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .saturating_sub(Duration::from_secs_f64(elapsed));
+                // Whole seconds, so the presence doesn't change on every update
+                started.as_secs() as i64 * 1000
+            });
+
+            return PresenceState {
+                details,
+                state: wpm
+                    .filter(|_| settings.show_wpm)
+                    .map(|wpm| format!("{wpm:.0} WPM")),
+                started,
+            };
+        }
+        Page::Stats(_) | Page::Review(_) => "Viewing results",
+        Page::History(_) => "Browsing history",
+        Page::Menu(_) | Page::Loading(_) | Page::Error(_) => "In menu",
+    };
+
+    PresenceState {
+        details: details.to_string(),
+        state: None,
+        started: None,
+    }
+}
@@ -1,10 +1,19 @@
 mod app;
+mod cache;
+mod check;
 mod config;
+#[cfg(feature = "discord")]
+mod discord;
 mod page;
+mod replay;
 mod statistics;
 mod utils;
 
-use std::{path::PathBuf, str::FromStr};
+use std::{
+    io::{Read, stdin},
+    path::PathBuf,
+    str::FromStr,
+};
 
 use app::App;
 use clap::Parser;
@@ -23,6 +32,35 @@ struct AppArgs {
     #[arg(short, long)]
     print_settings: bool,
 
+    /// Writes the loaded settings, modes and sources (including defaults) to the given directory
+    #[arg(long, value_name = "DIR")]
+    export_config: Option<PathBuf>,
+
+    /// Runs every source once with its default parameters and reports which ones fail
+    #[arg(long)]
+    check_sources: bool,
+
+    /// Removes the cached output of every source
+    #[arg(long)]
+    clear_cache: bool,
+
+    /// Starts a session typing the given text, skipping the menu. Use `-` to read it from stdin
+    #[arg(short, long)]
+    text: Option<String>,
+
+    /// Types the keystrokes of the given file into a session of `--text` without the interface,
+    /// and prints its statistics as JSON
+    #[arg(long, value_name = "FILE", requires = "text")]
+    replay: Option<PathBuf>,
+
+    /// Don't write the default modes and sources when none are found
+    #[arg(long)]
+    no_bootstrap: bool,
+
+    /// Seeds every session, so the same source and parameters always produce the same text
+    #[arg(long)]
+    seed: Option<u64>,
+
     /// Specifies a config location
     #[arg(short, long)]
     config: Option<String>,
@@ -33,7 +71,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let override_path = args.config.map(|dir| PathBuf::from_str(&dir)).transpose()?;
 
-    let config = Config::get(override_path)?;
+    let config = Config::get(override_path, !args.no_bootstrap, args.seed)?;
 
     if args.print_config {
         println!("{}", toml::to_string_pretty(&config)?);
@@ -45,7 +83,74 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
-    App::new(config).run()?;
+    if let Some(dir) = args.export_config {
+        config.export(&dir)?;
+        println!("Exported the config to {}", dir.display());
+        return Ok(());
+    }
+
+    if args.check_sources {
+        if !check::check_sources(&config) {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if args.clear_cache {
+        cache::clear(config.cache_dir())?;
+        println!(
+            "Cleared the source cache at {}",
+            config.cache_dir().display()
+        );
+        return Ok(());
+    }
+
+    let text = args
+        .text
+        .map(|text| {
+            if text != "-" {
+                return Ok(text);
+            }
+            read_text(stdin())
+        })
+        .transpose()?;
+
+    if let Some(file) = args.replay {
+        let events = replay::load_events(&file)?;
+        // Clap makes sure the text is given
+        let (export, ended) = replay::replay(&config, text.unwrap_or_default(), &events)?;
+        println!("{}", serde_json::to_string_pretty(&export)?);
+        if !ended {
+            std::process::exit(2);
+        }
+        return Ok(());
+    }
+
+    App::new(config, text).run()?;
 
     Ok(())
 }
+
+/// Reads the text of a session from `input`. Piped text usually ends with a line break, which
+/// would have to be typed without being visible, so trailing line breaks are left out
+fn read_text(mut input: impl Read) -> std::io::Result<String> {
+    let mut buf = String::new();
+    input.read_to_string(&mut buf)?;
+    Ok(buf.trim_end_matches(['\n', '\r']).to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::read_text;
+
+    #[test]
+    fn trailing_line_breaks_are_left_out() {
+        assert_eq!(read_text(&b"foo\n"[..]).unwrap(), "foo");
+        assert_eq!(read_text(&b"foo\r\n\n"[..]).unwrap(), "foo");
+        assert_eq!(
+            read_text(&b"fn main() {\n}\n"[..]).unwrap(),
+            "fn main() {\n}"
+        );
+        assert_eq!(read_text(&b"foo "[..]).unwrap(), "foo ");
+    }
+}
@@ -1,10 +1,11 @@
-use crossterm::event::Event;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{Frame, layout::Rect, text::Line};
 
 pub mod error;
 pub mod history;
 pub mod loadscreen;
 pub mod menu;
+pub mod review;
 pub mod session;
 pub mod stats;
 
@@ -12,6 +13,7 @@ pub use error::Error;
 pub use history::History;
 pub use loadscreen::Loading;
 pub use menu::Menu;
+pub use review::Review;
 pub use session::Session;
 pub use stats::Stats;
 
@@ -35,7 +37,7 @@ macro_rules! make_page_enum {
     };
 }
 
-make_page_enum!(Menu, Loading, Stats, Error, Session, History);
+make_page_enum!(Menu, Loading, Stats, Error, Session, History, Review);
 
 impl Page {
     pub fn render(&mut self, frame: &mut Frame, area: Rect, config: &Config) {
@@ -46,39 +48,70 @@ impl Page {
             Self::Stats(page) => page.render(frame, area, config),
             Self::Error(page) => page.render(frame, area, config),
             Self::History(page) => page.render(frame, area, config),
+            Self::Review(page) => page.render(frame, area, config),
         }
     }
 
     pub fn render_top(&mut self, config: &Config) -> Option<Line<'_>> {
         match self {
-            Self::Menu(_) => None,
-            Self::Loading(_) => None,
+            Self::Menu(page) => page.render_top(config),
+            Self::Loading(page) => page.render_top(config),
             Self::Session(page) => page.render_top(config),
             Self::Stats(page) => page.render_top(config),
             Self::Error(page) => page.render_top(config),
             Self::History(page) => page.render_top(config),
+            Self::Review(page) => page.render_top(config),
         }
     }
 
     pub fn handle_events(&mut self, event: &Event, config: &Config) -> Option<Message> {
         match self {
             Self::Menu(page) => page.handle_events(event, config),
-            Self::Loading(_) => None,
+            Self::Loading(page) => page.handle_events(event, config),
             Self::Session(page) => page.handle_events(event, config),
             Self::Stats(page) => page.handle_events(event, config),
             Self::Error(page) => page.handle_events(event, config),
             Self::History(page) => page.handle_events(event, config),
+            Self::Review(page) => page.handle_events(event, config),
         }
     }
 
+    /// Whether the page has progress that would be lost by quitting
+    pub fn is_busy(&self) -> bool {
+        matches!(self, Self::Session(session) if session.is_busy())
+    }
+
+    /// Whether the page shows only its content, without the surrounding frame
+    pub fn is_minimal(&self) -> bool {
+        matches!(self, Self::Session(session) if session.is_minimal())
+    }
+
+    /// Whether `key` is typed text on this page, which global keybindings shouldn't react to
+    pub fn captures_text(&self, key: &KeyEvent) -> bool {
+        let typing = match self {
+            Self::Session(_) => true,
+            Self::Menu(menu) => menu.is_typing(),
+            Self::Stats(stats) => stats.is_editing_tags(),
+            _ => false,
+        };
+        typing && matches!(key.code, KeyCode::Char(_)) && !has_command_modifier(key)
+    }
+
     pub fn poll(&mut self, config: &Config) -> Option<Message> {
         match self {
-            Self::Menu(_) => None,
+            Self::Menu(page) => page.poll(config),
             Self::Loading(page) => page.poll(config),
             Self::Session(page) => page.poll(config),
             Self::Stats(_) => None,
             Self::Error(_) => None,
             Self::History(_) => None,
+            Self::Review(_) => None,
         }
     }
 }
+
+/// Whether `key` is held with a modifier that turns typing into a command, like `ctrl-q`
+pub fn has_command_modifier(key: &KeyEvent) -> bool {
+    key.modifiers
+        .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+}
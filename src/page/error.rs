@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-use crossterm::event::{Event, KeyCode};
+use crossterm::event::Event;
 use ratatui::{
     layout::{Constraint, Rect},
     style::{Style, Stylize},
@@ -9,7 +9,7 @@ use ratatui::{
 };
 
 use crate::{
-    config::Config,
+    config::{Config, keymap::Action},
     utils::{center, centered_padding, height_of_lines},
 };
 
@@ -40,7 +40,7 @@ impl Error {
 
         let mut lines = vec![
             Line::styled(
-                "[Error]",
+                config.tr("error.title"),
                 Style::new().bold().fg(config.settings.theme.text.error),
             )
             .centered(),
@@ -65,16 +65,17 @@ impl Error {
         frame.render_widget(text, area);
     }
 
-    pub fn render_top(&self, _config: &crate::config::Config) -> Option<Line<'_>> {
-        Some(Line::from("<Enter> to return to menu"))
+    pub fn render_top(&self, config: &crate::config::Config) -> Option<Line<'_>> {
+        Some(Line::from(config.tr("error.return").to_string()))
     }
 
-    pub fn handle_events(&self, event: &Event, _config: &crate::config::Config) -> Option<Message> {
+    pub fn handle_events(&self, event: &Event, config: &crate::config::Config) -> Option<Message> {
+        let keys = &config.settings.keybindings;
         if let Event::Key(key) = event
             && key.is_press()
         {
             return match key.code {
-                KeyCode::Enter => Some(Message::Reset),
+                _ if keys.is(Action::Select, key) => Some(Message::Reset),
                 _ => None,
             };
         };
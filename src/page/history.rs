@@ -1,10 +1,10 @@
-use crossterm::event::{Event, KeyCode};
+use crossterm::event::Event;
 use derive_more::{Display, From};
 use ratatui::{
     Frame,
     layout::{Constraint, Layout, Rect},
     style::{Color, Style, Stylize},
-    text::{Line, Span, ToSpan},
+    text::{Line, Span},
     widgets::{Axis, Block, Chart, Dataset, GraphType, LegendPosition, List, Paragraph},
 };
 use thiserror::Error;
@@ -12,11 +12,16 @@ use web_time::SystemTime;
 
 use crate::{
     app::Message,
-    config::Config,
-    statistics::{SessionStatistics, StatisticsError},
-    utils::{ROUNDED_BLOCK, center},
+    config::{Config, keymap::Action},
+    statistics::{AggregateStats, SerializableMeasurement, SessionStatistics, StatisticsError},
+    utils::{ROUNDED_BLOCK, center, fade},
 };
 
+/// Amount of characters listed as most missed in the lifetime totals
+const MOST_MISSED_LIMIT: usize = 5;
+/// Color of the second session in the comparison. The first one uses the theme
+const COMPARE_B_COLOR: Color = Color::Cyan;
+
 /// Page: History
 ///
 /// Shows saved statistics history and improvements over time.
@@ -25,12 +30,28 @@ pub struct History {
     sessions: Vec<SessionStatistics>,
     selected_index: usize,
     view_mode: ViewMode,
+    /// Mode and source name that the filtered trends view is limited to
+    filter: Option<(String, String)>,
+    /// Whether a moving average is overlayed on the WPM trend
+    smoothing: bool,
+    /// Lifetime totals over all sessions
+    aggregate: AggregateStats,
+    /// Index of the first session marked for comparison
+    compare_a: Option<usize>,
+    /// Index of the second session marked for comparison
+    compare_b: Option<usize>,
+    /// Whether deleting the selected session waits for confirmation
+    confirming_delete: bool,
 }
 
 #[derive(Debug, Clone, Copy)]
 enum ViewMode {
     List,
     Trends,
+    FilteredTrends,
+    Totals,
+    /// Two marked sessions side by side
+    Compare,
 }
 
 #[derive(Debug, From, Error, Display)]
@@ -46,12 +67,121 @@ impl History {
         };
 
         Ok(Self {
+            aggregate: AggregateStats::from_sessions(&sessions),
             sessions,
             selected_index: 0,
             view_mode: ViewMode::List,
+            filter: None,
+            smoothing: false,
+            compare_a: None,
+            compare_b: None,
+            confirming_delete: false,
         })
     }
 
+    /// Returns the sessions shown in the trends view, respecting the active filter
+    fn trend_sessions(&self) -> Vec<&SessionStatistics> {
+        self.sessions
+            .iter()
+            .filter(|session| {
+                self.filter.as_ref().is_none_or(|(mode, source)| {
+                    &session.session_config.mode_name == mode
+                        && &session.session_config.source_name == source
+                })
+            })
+            .collect()
+    }
+
+    fn cycle_view_mode(&mut self) {
+        self.view_mode = match self.view_mode {
+            ViewMode::List => ViewMode::Trends,
+            ViewMode::Trends => {
+                self.filter = self.get_selected_session().map(|session| {
+                    (
+                        session.session_config.mode_name.clone(),
+                        session.session_config.source_name.clone(),
+                    )
+                });
+                ViewMode::FilteredTrends
+            }
+            ViewMode::FilteredTrends => {
+                self.filter = None;
+                ViewMode::Totals
+            }
+            ViewMode::Totals => ViewMode::List,
+            ViewMode::Compare => {
+                self.compare_a = None;
+                self.compare_b = None;
+                ViewMode::List
+            }
+        };
+    }
+
+    /// Marks or unmarks the selected session for comparison. Marking a second session opens the
+    /// comparison
+    fn toggle_compare(&mut self) {
+        if self.sessions.is_empty() {
+            return;
+        }
+        let index = Some(self.selected_index);
+
+        if self.compare_a == index {
+            self.compare_a = self.compare_b.take();
+        } else if self.compare_b == index {
+            self.compare_b = None;
+        } else if self.compare_a.is_none() {
+            self.compare_a = index;
+        } else {
+            self.compare_b = index;
+            self.view_mode = ViewMode::Compare;
+        }
+    }
+
+    /// Deletes the selected session from disk and the list
+    fn delete_selected(&mut self, config: &Config) -> Result<(), StatisticsError> {
+        let Some(session) = self.get_selected_session() else {
+            return Ok(());
+        };
+        if let Some(stats_manager) = &config.statistics_manager {
+            stats_manager.delete_session(&session.session_id)?;
+        }
+        self.remove_session(self.selected_index);
+        Ok(())
+    }
+
+    /// Removes a session from the list, keeping the selection and the comparison marks on the
+    /// same sessions where possible
+    fn remove_session(&mut self, index: usize) {
+        if index >= self.sessions.len() {
+            return;
+        }
+        self.sessions.remove(index);
+        self.aggregate = AggregateStats::from_sessions(&self.sessions);
+
+        let shift = |mark: Option<usize>| match mark {
+            Some(mark) if mark == index => None,
+            Some(mark) if mark > index => Some(mark - 1),
+            mark => mark,
+        };
+        self.compare_a = shift(self.compare_a);
+        self.compare_b = shift(self.compare_b);
+        if self.compare_a.is_none() {
+            self.compare_a = self.compare_b.take();
+        }
+
+        // The next session takes the place of the removed one, unless it was the last
+        if self.selected_index > index || self.selected_index >= self.sessions.len() {
+            self.selected_index = self.selected_index.saturating_sub(1);
+        }
+    }
+
+    fn compared_sessions(&self) -> Option<(&SessionStatistics, &SessionStatistics)> {
+        Some((
+            self.sessions.get(self.compare_a?)?,
+            self.sessions.get(self.compare_b?)?,
+        ))
+    }
+
     fn get_selected_session(&self) -> Option<&SessionStatistics> {
         self.sessions.get(self.selected_index)
     }
@@ -74,7 +204,7 @@ impl History {
         self.selected_index = (self.selected_index + 1) % self.sessions.len();
     }
 
-    fn format_timestamp(timestamp: SystemTime) -> String {
+    fn format_timestamp(timestamp: SystemTime, config: &Config) -> String {
         let now = SystemTime::now();
         let duration = now.duration_since(timestamp).unwrap_or_default();
         let secs = duration.as_secs();
@@ -82,24 +212,24 @@ impl History {
         let hours = (secs % 86400) / 3600;
         let minutes = (secs % 3600) / 60;
 
+        let args: [(&str, &dyn std::fmt::Display); 3] =
+            [("days", &days), ("hours", &hours), ("minutes", &minutes)];
         if days > 0 {
-            format!("{}d {}h {}m ago", days, hours, minutes)
+            config.tr_with("history.days_ago", &args)
         } else if hours > 0 {
-            format!("{}h {}m ago", hours, minutes)
+            config.tr_with("history.hours_ago", &args)
         } else if minutes > 0 {
-            format!("{}m ago", minutes)
+            config.tr_with("history.minutes_ago", &args)
         } else {
-            "just now".to_string()
+            config.tr("history.just_now").to_string()
         }
     }
 
     fn render_list_view(&self, frame: &mut Frame, area: Rect, config: &Config) {
         if self.sessions.is_empty() {
-            let no_data = Paragraph::new(
-                "No statistics saved yet.\nComplete a typing session to see your history here.",
-            )
-            .block(ROUNDED_BLOCK.title("Statistics History".to_span().bold()))
-            .centered();
+            let no_data = Paragraph::new(config.tr("history.no_sessions"))
+                .block(ROUNDED_BLOCK.title(Span::from(config.tr("history.title")).bold()))
+                .centered();
             frame.render_widget(no_data, area);
             return;
         }
@@ -123,9 +253,20 @@ impl History {
                 "{} / {}",
                 session.session_config.mode_name, session.session_config.source_name
             );
-            let wpm = format!("{:.1} wpm", session.statistics.wpm_actual);
+            let wpm = format!(
+                "{:.1} {}",
+                session.statistics.wpm_actual,
+                config.tr("history.wpm_unit")
+            );
             let accuracy = format!("{:.0}%", session.statistics.accuracy_actual);
-            let time_ago = Self::format_timestamp(session.timestamp);
+            let time_ago = Self::format_timestamp(session.timestamp, config);
+            let mark = if self.compare_a == Some(i) {
+                " [A]"
+            } else if self.compare_b == Some(i) {
+                " [B]"
+            } else {
+                ""
+            };
 
             Line::from(vec![
                 Span::raw(selector),
@@ -136,70 +277,106 @@ impl History {
                     ),
                     style,
                 ),
+                Span::raw(mark).bold(),
             ])
         });
 
-        let list = List::new(items).block(ROUNDED_BLOCK.title("Session History".to_span().bold()));
+        let list = List::new(items)
+            .block(ROUNDED_BLOCK.title(Span::from(config.tr("history.session_history")).bold()));
         frame.render_widget(list, list_area);
 
         // Render selected session details
         if let Some(session) = self.get_selected_session() {
-            let settings = vec![
-                Line::from(format!("Mode: {}", session.session_config.mode_name)),
-                Line::from(format!("Source: {}", session.session_config.source_name)),
-                Line::from(format!(
-                    "Deletions: {}",
-                    if session.session_config.allow_deletions {
-                        "Allowed"
+            let line =
+                |key: &str, value: String| Line::from(format!("{}: {value}", config.tr(key)));
+            let allowed = |allowed: bool| {
+                config
+                    .tr(if allowed {
+                        "history.allowed"
                     } else {
-                        "Disabled"
-                    }
-                )),
-                Line::from(format!(
-                    "Errors: {}",
-                    if session.session_config.allow_errors {
-                        "Allowed"
-                    } else {
-                        "Disabled"
-                    }
-                )),
-                session.session_config.time_limit.map_or_else(
-                    || Line::from("Time Limit: None"),
-                    |limit| Line::from(format!("Time Limit: {:.0}s", limit)),
+                        "history.disabled"
+                    })
+                    .to_string()
+            };
+            let none = || config.tr("history.none").to_string();
+
+            let settings = vec![
+                line("history.mode", session.session_config.mode_name.clone()),
+                line("history.source", session.session_config.source_name.clone()),
+                line(
+                    "history.deletions",
+                    allowed(session.session_config.allow_deletions),
+                ),
+                line(
+                    "history.errors",
+                    allowed(session.session_config.allow_errors),
+                ),
+                line(
+                    "history.time_limit",
+                    session
+                        .session_config
+                        .time_limit
+                        .map_or_else(none, |limit| format!("{limit:.0}s")),
+                ),
+                line(
+                    "history.word_limit",
+                    session
+                        .session_config
+                        .words_typed_limit
+                        .map_or_else(none, |limit| limit.to_string()),
                 ),
-                session.session_config.words_typed_limit.map_or_else(
-                    || Line::from("Word Limit: None"),
-                    |limit| Line::from(format!("Word Limit: {}", limit)),
+                line(
+                    "history.end_reason",
+                    config.tr(session.end_reason.key()).to_string(),
+                ),
+                line(
+                    "history.tags",
+                    if session.tags.is_empty() {
+                        none()
+                    } else {
+                        session.tags.join(", ")
+                    },
                 ),
             ];
             let stats = vec![
-                Line::from(format!(
-                    "Time: {:.2} min",
-                    session.statistics.duration / 60.0
-                )),
-                Line::from(format!(
-                    "WPM (Actual): {:.2}",
-                    session.statistics.wpm_actual
-                )),
-                Line::from(format!("WPM (Raw): {:.2}", session.statistics.wpm_raw)),
-                Line::from(format!(
-                    "Accuracy: {:.1}%",
-                    session.statistics.accuracy_actual
-                )),
-                Line::from(format!(
-                    "Consistency: {:.1}%",
-                    session.statistics.consistency_actual_percent
-                )),
-                Line::from(format!("Errors: {}", session.statistics.errors)),
-                Line::from(format!("Corrections: {}", session.statistics.corrections)),
-                Line::from(format!(
-                    "Correct Characters: {}",
-                    session.statistics.corrects
-                )),
-                Line::from(format!("Total Added: {}", session.statistics.adds)),
+                line(
+                    "history.time",
+                    format!(
+                        "{:.2} {}",
+                        session.statistics.duration / 60.0,
+                        config.tr("history.minutes_unit")
+                    ),
+                ),
+                line(
+                    "history.wpm_actual",
+                    format!("{:.2}", session.statistics.wpm_actual),
+                ),
+                line(
+                    "history.wpm_raw",
+                    format!("{:.2}", session.statistics.wpm_raw),
+                ),
+                line(
+                    "history.accuracy",
+                    format!("{:.1}%", session.statistics.accuracy_actual),
+                ),
+                line(
+                    "history.consistency",
+                    format!("{:.1}%", session.statistics.consistency_actual_percent),
+                ),
+                line("history.errors", session.statistics.errors.to_string()),
+                line(
+                    "history.corrections",
+                    session.statistics.corrections.to_string(),
+                ),
+                line(
+                    "history.correct_characters",
+                    session.statistics.corrects.to_string(),
+                ),
+                line("history.total_added", session.statistics.adds.to_string()),
             ];
 
-            let outer_block = ROUNDED_BLOCK.title("Session Details".to_span().bold());
+            let outer_block =
+                ROUNDED_BLOCK.title(Span::from(config.tr("history.session_details")).bold());
             let inner_area = outer_block.inner(detail_area);
 
             let [settings_area, stats_area] =
@@ -208,22 +385,89 @@ impl History {
 
             frame.render_widget(outer_block, detail_area);
             frame.render_widget(
-                Paragraph::new(settings)
-                    .block(Block::new().title(Span::from("Settings").bold().underlined())),
+                Paragraph::new(settings).block(
+                    Block::new().title(
+                        Span::from(config.tr("history.settings"))
+                            .bold()
+                            .underlined(),
+                    ),
+                ),
                 settings_area,
             );
             frame.render_widget(
-                Paragraph::new(stats)
-                    .block(Block::new().title(Span::from("Stats").bold().underlined())),
+                Paragraph::new(stats).block(
+                    Block::new().title(Span::from(config.tr("history.stats")).bold().underlined()),
+                ),
                 stats_area,
             );
         }
     }
 
+    fn render_totals_view(&self, frame: &mut Frame, area: Rect, config: &Config) {
+        let aggregate = &self.aggregate;
+        let line = |key: &str, value: String| Line::from(format!("{}: {value}", config.tr(key)));
+        let totals = vec![
+            line("history.sessions", aggregate.session_count.to_string()),
+            line(
+                "history.time",
+                format!(
+                    "{:.2} {}",
+                    aggregate.total_duration / 60.0,
+                    config.tr("history.minutes_unit")
+                ),
+            ),
+            line(
+                "history.characters_typed",
+                aggregate.total_characters.to_string(),
+            ),
+            line("history.errors", aggregate.total_errors.to_string()),
+            line(
+                "history.corrections",
+                aggregate.total_corrections.to_string(),
+            ),
+            line(
+                "history.wpm_average",
+                format!("{:.2}", aggregate.average_wpm),
+            ),
+            line("history.wpm_best", format!("{:.2}", aggregate.best_wpm)),
+            line(
+                "history.accuracy_average",
+                format!("{:.1}%", aggregate.average_accuracy),
+            ),
+            line(
+                "history.most_missed",
+                aggregate
+                    .most_missed(MOST_MISSED_LIMIT)
+                    .iter()
+                    .map(|(character, count)| format!("'{character}' ({count})"))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+        ];
+
+        frame.render_widget(
+            Paragraph::new(totals).block(
+                ROUNDED_BLOCK.title(Span::from(config.tr("history.lifetime_totals")).bold()),
+            ),
+            area,
+        );
+    }
+
     fn render_trends_view(&self, frame: &mut Frame, area: Rect, config: &Config) {
-        if self.sessions.len() < 2 {
-            let no_data = Paragraph::new("Need at least 2 sessions to show trends.\nComplete more typing sessions to see your progress.")
-                .block(ROUNDED_BLOCK.title("Trends".to_span().bold()))
+        let sessions = self.trend_sessions();
+
+        if sessions.len() < 2 {
+            let text = self.filter.as_ref().map_or_else(
+                || config.tr("history.not_enough_sessions").to_string(),
+                |(mode, source)| {
+                    config.tr_with(
+                        "history.not_enough_filtered_sessions",
+                        &[("mode", mode), ("source", source)],
+                    )
+                },
+            );
+            let no_data = Paragraph::new(text)
+                .block(ROUNDED_BLOCK.title(Span::from(config.tr("history.trends")).bold()))
                 .centered();
             frame.render_widget(no_data, area);
             return;
@@ -236,7 +480,7 @@ impl History {
         let mut wpm_data = Vec::new();
         let mut accuracy_data = Vec::new();
 
-        let sessions_reversed: Vec<_> = self.sessions.iter().rev().collect();
+        let sessions_reversed: Vec<_> = sessions.iter().rev().collect();
 
         for (i, session) in sessions_reversed.iter().enumerate() {
             let x = i as f64;
@@ -246,14 +490,35 @@ impl History {
 
         let theme = &config.settings.theme.plot;
 
+        // Dim the raw series when the moving average is shown on top of it
+        let wpm_color = if self.smoothing {
+            fade(theme.actual_wpm, config.settings.theme.term_bg, 0.5, true)
+        } else {
+            theme.actual_wpm
+        };
+
         // WPM trend chart
         let wpm_dataset = Dataset::default()
-            .name("WPM")
+            .name(config.tr("history.wpm"))
             .marker(theme.line_symbol.as_marker())
             .graph_type(GraphType::Line)
-            .style(Style::default().fg(theme.actual_wpm))
+            .style(Style::default().fg(wpm_color))
             .data(&wpm_data);
 
+        let window = config.settings.statistic.trend_smoothing_window;
+        let average_data = moving_average(&wpm_data, window);
+        let mut wpm_datasets = vec![wpm_dataset];
+        if self.smoothing {
+            wpm_datasets.push(
+                Dataset::default()
+                    .name(config.tr_with("history.wpm_moving_average", &[("window", &window)]))
+                    .marker(theme.line_symbol.as_marker())
+                    .graph_type(GraphType::Line)
+                    .style(Style::default().fg(theme.actual_wpm))
+                    .data(&average_data),
+            );
+        }
+
         let (wpm_min, wpm_max) = wpm_data
             .iter()
             .fold((f64::INFINITY, f64::NEG_INFINITY), |acc, (_, y)| {
@@ -266,18 +531,27 @@ impl History {
             [0.0, 100.0]
         };
 
-        let wpm_chart = Chart::new(vec![wpm_dataset])
-            .block(ROUNDED_BLOCK.title("WPM Progress".to_span().bold()))
+        let filter_title = self
+            .filter
+            .as_ref()
+            .map(|(mode, source)| format!(" ({mode} / {source})"))
+            .unwrap_or_default();
+
+        let wpm_chart = Chart::new(wpm_datasets)
+            .block(
+                ROUNDED_BLOCK
+                    .title(format!("{}{filter_title}", config.tr("history.wpm_progress")).bold()),
+            )
             .x_axis(
                 Axis::default()
-                    .title("Sessions")
+                    .title(config.tr("history.sessions"))
                     .style(Style::default().fg(Color::Gray))
-                    .labels((1..=self.sessions.len()).map(|i| i.to_string()))
+                    .labels((1..=sessions.len()).map(|i| i.to_string()))
                     .bounds([0.0, (sessions_reversed.len() - 1) as f64]),
             )
             .y_axis(
                 Axis::default()
-                    .title("WPM")
+                    .title(config.tr("history.wpm"))
                     .style(Style::default().fg(Color::Gray))
                     .labels((wpm_min as usize..=wpm_max as usize).map(|wpm| wpm.to_string()))
                     .bounds(wpm_bounds),
@@ -288,31 +562,236 @@ impl History {
 
         // Accuracy trend chart
         let accuracy_dataset = Dataset::default()
-            .name("Accuracy")
+            .name(config.tr("history.accuracy"))
             .marker(theme.line_symbol.as_marker())
             .graph_type(GraphType::Line)
             .style(Style::default().fg(theme.accuracy))
             .data(&accuracy_data);
 
-        let accuracy_chart = Chart::new(vec![accuracy_dataset])
-            .block(ROUNDED_BLOCK.title("Accuracy Progress".to_span().bold()))
-            .x_axis(
-                Axis::default()
-                    .title("Sessions")
-                    .style(Style::default().fg(Color::Gray))
-                    .labels((1..=self.sessions.len()).map(|i| i.to_string()))
-                    .bounds([0.0, (sessions_reversed.len() - 1) as f64]),
-            )
-            .y_axis(
-                Axis::default()
-                    .title("Accuracy (%)")
-                    .style(Style::default().fg(Color::Gray))
-                    .labels(["0%", "50%", "100%"])
-                    .bounds([0.0, 100.0]),
+        let accuracy_chart =
+            Chart::new(vec![accuracy_dataset])
+                .block(ROUNDED_BLOCK.title(
+                    format!("{}{filter_title}", config.tr("history.accuracy_progress")).bold(),
+                ))
+                .x_axis(
+                    Axis::default()
+                        .title(config.tr("history.sessions"))
+                        .style(Style::default().fg(Color::Gray))
+                        .labels((1..=sessions.len()).map(|i| i.to_string()))
+                        .bounds([0.0, (sessions_reversed.len() - 1) as f64]),
+                )
+                .y_axis(
+                    Axis::default()
+                        .title(config.tr("history.accuracy_percent"))
+                        .style(Style::default().fg(Color::Gray))
+                        .labels(["0%", "50%", "100%"])
+                        .bounds([0.0, 100.0]),
+                )
+                .legend_position(Some(LegendPosition::BottomLeft));
+
+        frame.render_widget(accuracy_chart, accuracy_area);
+    }
+
+    fn render_compare_view(&self, frame: &mut Frame, area: Rect, config: &Config) {
+        let Some((a, b)) = self.compared_sessions() else {
+            return;
+        };
+
+        let [charts_area, table_area] =
+            Layout::vertical([Constraint::Percentage(65), Constraint::Percentage(35)]).areas(area);
+        let [wpm_area, accuracy_area] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(charts_area);
+
+        let theme = &config.settings.theme.plot;
+        let name = |label: &str, session: &SessionStatistics| {
+            format!(
+                "{label}: {}",
+                Self::format_timestamp(session.timestamp, config)
             )
-            .legend_position(Some(LegendPosition::BottomLeft));
+        };
+
+        // Sessions of different lengths share the time axis, the shorter one just ends earlier
+        let duration = a.statistics.duration.max(b.statistics.duration).max(1.0);
+        let time_axis = || {
+            Axis::default()
+                .title(config.tr("history.time"))
+                .style(Style::default().fg(Color::Gray))
+                .labels(["0s".to_string(), format!("{duration:.0}s")])
+                .bounds([0.0, duration])
+        };
+
+        let wpm_a = session_trend(a, |m| m.wpm_actual, a.statistics.wpm_actual);
+        let wpm_b = session_trend(b, |m| m.wpm_actual, b.statistics.wpm_actual);
+        let (wpm_min, wpm_max) = wpm_a
+            .iter()
+            .chain(&wpm_b)
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |acc, (_, y)| {
+                (acc.0.min(*y), acc.1.max(*y))
+            });
+        let wpm_bounds = if wpm_min.is_finite() && wpm_max.is_finite() {
+            [(wpm_min - 5.0).max(0.0), wpm_max + 5.0]
+        } else {
+            [0.0, 100.0]
+        };
+
+        let wpm_chart = Chart::new(vec![
+            Dataset::default()
+                .name(name("A", a))
+                .marker(theme.line_symbol.as_marker())
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(theme.actual_wpm))
+                .data(&wpm_a),
+            Dataset::default()
+                .name(name("B", b))
+                .marker(theme.line_symbol.as_marker())
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(COMPARE_B_COLOR))
+                .data(&wpm_b),
+        ])
+        .block(ROUNDED_BLOCK.title(Span::from(config.tr("history.wpm")).bold()))
+        .x_axis(time_axis())
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(Color::Gray))
+                .labels([
+                    format!("{:.0}", wpm_bounds[0]),
+                    format!("{:.0}", wpm_bounds[1]),
+                ])
+                .bounds(wpm_bounds),
+        )
+        .legend_position(Some(LegendPosition::BottomLeft));
+
+        frame.render_widget(wpm_chart, wpm_area);
+
+        let accuracy_a = session_trend(a, |m| m.accuracy_actual, a.statistics.accuracy_actual);
+        let accuracy_b = session_trend(b, |m| m.accuracy_actual, b.statistics.accuracy_actual);
+
+        let accuracy_chart = Chart::new(vec![
+            Dataset::default()
+                .name(name("A", a))
+                .marker(theme.line_symbol.as_marker())
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(theme.actual_wpm))
+                .data(&accuracy_a),
+            Dataset::default()
+                .name(name("B", b))
+                .marker(theme.line_symbol.as_marker())
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(COMPARE_B_COLOR))
+                .data(&accuracy_b),
+        ])
+        .block(ROUNDED_BLOCK.title(Span::from(config.tr("history.accuracy")).bold()))
+        .x_axis(time_axis())
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(Color::Gray))
+                .labels(["0%", "50%", "100%"])
+                .bounds([0.0, 100.0]),
+        )
+        .legend_position(Some(LegendPosition::BottomLeft));
 
         frame.render_widget(accuracy_chart, accuracy_area);
+
+        // Metric table: the values of both sessions and the change from A to B
+        let diff = b.statistics.diff(&a.statistics);
+        let mut rows = vec![
+            (
+                config.tr("history.mode").to_string(),
+                a.session_config.mode_name.clone(),
+                b.session_config.mode_name.clone(),
+                String::new(),
+            ),
+            (
+                config.tr("history.source").to_string(),
+                a.session_config.source_name.clone(),
+                b.session_config.source_name.clone(),
+                String::new(),
+            ),
+            (
+                config.tr("history.time").to_string(),
+                format!("{:.0}s", a.statistics.duration),
+                format!("{:.0}s", b.statistics.duration),
+                format!("{:+.0}s", b.statistics.duration - a.statistics.duration),
+            ),
+        ];
+        let metrics = [
+            (
+                "history.wpm_actual",
+                a.statistics.wpm_actual,
+                b.statistics.wpm_actual,
+                diff.wpm_actual,
+                "",
+            ),
+            (
+                "history.wpm_raw",
+                a.statistics.wpm_raw,
+                b.statistics.wpm_raw,
+                diff.wpm_raw,
+                "",
+            ),
+            (
+                "history.accuracy",
+                a.statistics.accuracy_actual,
+                b.statistics.accuracy_actual,
+                diff.accuracy_actual,
+                "%",
+            ),
+            (
+                "history.consistency",
+                a.statistics.consistency_actual_percent,
+                b.statistics.consistency_actual_percent,
+                diff.consistency_actual_percent,
+                "%",
+            ),
+        ];
+        rows.extend(metrics.map(|(key, value_a, value_b, change, unit)| {
+            (
+                config.tr(key).to_string(),
+                format!("{value_a:.2}{unit}"),
+                format!("{value_b:.2}{unit}"),
+                format!("{change:+.2}{unit}"),
+            )
+        }));
+        rows.push((
+            config.tr("history.errors").to_string(),
+            a.statistics.errors.to_string(),
+            b.statistics.errors.to_string(),
+            format!("{:+}", diff.errors),
+        ));
+
+        // Columns are padded to their longest cell, so they line up in any language
+        let width = |cell: fn(&(String, String, String, String)) -> &String| {
+            rows.iter()
+                .map(|row| cell(row).chars().count())
+                .max()
+                .unwrap_or_default()
+        };
+        let label_width = width(|row| &row.0);
+        let a_width = width(|row| &row.1);
+        let b_width = width(|row| &row.2);
+        let lines = std::iter::once(
+            Line::from(format!(
+                "{:<label_width$} | {:<a_width$} | {:<b_width$} | {}",
+                "",
+                "A",
+                "B",
+                config.tr("history.difference")
+            ))
+            .bold(),
+        )
+        .chain(rows.iter().map(|(label, value_a, value_b, change)| {
+            Line::from(format!(
+                "{label:<label_width$} | {value_a:<a_width$} | {value_b:<b_width$} | {change}"
+            ))
+        }))
+        .collect::<Vec<_>>();
+
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(ROUNDED_BLOCK.title(Span::from(config.tr("history.comparison")).bold())),
+            table_area,
+        );
     }
 }
 
@@ -323,37 +802,60 @@ impl History {
 
         match self.view_mode {
             ViewMode::List => self.render_list_view(frame, area, config),
-            ViewMode::Trends => self.render_trends_view(frame, area, config),
+            ViewMode::Trends | ViewMode::FilteredTrends => {
+                self.render_trends_view(frame, area, config)
+            }
+            ViewMode::Totals => self.render_totals_view(frame, area, config),
+            ViewMode::Compare => self.render_compare_view(frame, area, config),
         }
     }
 
-    pub fn render_top(&self, _config: &Config) -> Option<Line<'_>> {
-        match self.view_mode {
-            ViewMode::List => Some(Line::raw(
-                "<Enter> menu | <Tab> trends | <Up/Down> navigate",
-            )),
-            ViewMode::Trends => Some(Line::raw("<Enter> menu | <Tab> list view")),
+    pub fn render_top(&self, config: &Config) -> Option<Line<'_>> {
+        if self.confirming_delete {
+            return Some(Line::raw(config.tr("history.confirm_delete").to_string()));
         }
+        let keys = match self.view_mode {
+            ViewMode::List => "history.list_keys",
+            ViewMode::Trends => "history.trends_keys",
+            ViewMode::FilteredTrends => "history.filtered_trends_keys",
+            ViewMode::Totals => "history.totals_keys",
+            ViewMode::Compare => "history.compare_keys",
+        };
+        Some(Line::raw(config.tr(keys).to_string()))
     }
 
-    pub fn handle_events(&mut self, event: &Event, _config: &Config) -> Option<Message> {
+    pub fn handle_events(&mut self, event: &Event, config: &Config) -> Option<Message> {
+        let keys = &config.settings.keybindings;
         if let Event::Key(key) = event
             && key.is_press()
         {
+            // Any other key cancels the deletion
+            if std::mem::take(&mut self.confirming_delete) && keys.is(Action::Delete, key) {
+                return self
+                    .delete_selected(config)
+                    .err()
+                    .map(|error| Message::Error(Box::new(error)));
+            }
+
             match key.code {
-                KeyCode::Enter => return Some(Message::Reset),
-                KeyCode::Tab => {
-                    self.view_mode = match self.view_mode {
-                        ViewMode::List => ViewMode::Trends,
-                        ViewMode::Trends => ViewMode::List,
-                    };
+                _ if keys.is(Action::Select, key) => return Some(Message::Reset),
+                _ if keys.is(Action::NextView, key) => self.cycle_view_mode(),
+                _ if keys.is(Action::Delete, key)
+                    && matches!(self.view_mode, ViewMode::List)
+                    && !self.sessions.is_empty() =>
+                {
+                    self.confirming_delete = true;
+                }
+                _ if keys.is(Action::Smoothing, key) => self.smoothing = !self.smoothing,
+                _ if keys.is(Action::Compare, key) && matches!(self.view_mode, ViewMode::List) => {
+                    self.toggle_compare();
                 }
-                KeyCode::Up | KeyCode::Char('k') => {
+                _ if keys.is(Action::Up, key) => {
                     if matches!(self.view_mode, ViewMode::List) {
                         self.move_selection_up();
                     }
                 }
-                KeyCode::Down | KeyCode::Char('j') => {
+                _ if keys.is(Action::Down, key) => {
                     if matches!(self.view_mode, ViewMode::List) {
                         self.move_selection_down();
                     }
@@ -365,3 +867,180 @@ impl History {
         None
     }
 }
+
+/// Plots `metric` over the time of a session.
+///
+/// Sessions saved before the measurements were recorded are shown as a flat line at `average`.
+fn session_trend(
+    session: &SessionStatistics,
+    metric: fn(&SerializableMeasurement) -> f64,
+    average: f64,
+) -> Vec<(f64, f64)> {
+    if session.measurements.is_empty() {
+        return vec![(0.0, average), (session.statistics.duration, average)];
+    }
+
+    session
+        .measurements
+        .iter()
+        .map(|measurement| (measurement.timestamp, metric(measurement)))
+        .collect()
+}
+
+/// Computes the trailing moving average of `data` over `window` points.
+///
+/// The first points average over as many points as are available.
+fn moving_average(data: &[(f64, f64)], window: usize) -> Vec<(f64, f64)> {
+    let window = window.max(1);
+    data.iter()
+        .enumerate()
+        .map(|(i, (x, _))| {
+            let start = (i + 1).saturating_sub(window);
+            let points = &data[start..=i];
+            let sum: f64 = points.iter().map(|(_, y)| y).sum();
+            (*x, sum / points.len() as f64)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::statistics::{AggregateStats, SessionStatistics};
+
+    use super::{History, ViewMode, moving_average, session_trend};
+
+    fn session(duration: f64, measurements: &str) -> SessionStatistics {
+        serde_json::from_str(&format!(
+            r#"{{
+                "timestamp": {{ "secs_since_epoch": 1700000000, "nanos_since_epoch": 0 }},
+                "session_id": "test",
+                "session_config": {{
+                    "mode_name": "Normal",
+                    "source_name": "English",
+                    "time_limit": null,
+                    "words_typed_limit": null,
+                    "allow_deletions": true,
+                    "allow_errors": true
+                }},
+                "statistics": {{
+                    "duration": {duration},
+                    "wpm_actual": 60.0,
+                    "wpm_raw": 60.0,
+                    "accuracy_actual": 95.0,
+                    "accuracy_raw": 95.0,
+                    "consistency_actual_percent": 80.0,
+                    "adds": 100,
+                    "corrects": 95,
+                    "errors": 5,
+                    "corrections": 0,
+                    "deletes": 0,
+                    "wrong_deletes": 0
+                }},
+                "measurements": [{measurements}]
+            }}"#
+        ))
+        .unwrap()
+    }
+
+    fn history(durations: &[f64]) -> History {
+        History {
+            sessions: durations
+                .iter()
+                .map(|duration| session(*duration, ""))
+                .collect(),
+            selected_index: 0,
+            view_mode: ViewMode::List,
+            filter: None,
+            smoothing: false,
+            aggregate: AggregateStats::default(),
+            compare_a: None,
+            compare_b: None,
+            confirming_delete: false,
+        }
+    }
+
+    #[test]
+    fn removing_sessions_keeps_selection_and_marks() {
+        let mut history = history(&[10.0, 20.0, 30.0, 40.0]);
+        history.selected_index = 1;
+        history.compare_a = Some(1);
+        history.compare_b = Some(3);
+
+        history.remove_session(1);
+
+        // The next session is selected, the marks follow their sessions
+        assert_eq!(history.selected_index, 1);
+        assert_eq!(history.sessions[1].statistics.duration, 30.0);
+        assert_eq!(history.compare_a, Some(2));
+        assert_eq!(history.compare_b, None);
+        assert_eq!(history.aggregate.session_count, 3);
+    }
+
+    #[test]
+    fn removing_the_last_and_only_sessions() {
+        let mut history = history(&[10.0, 20.0]);
+        history.selected_index = 1;
+
+        history.remove_session(1);
+        assert_eq!(history.selected_index, 0);
+
+        history.remove_session(0);
+        assert!(history.sessions.is_empty());
+        assert_eq!(history.selected_index, 0);
+        assert!(history.get_selected_session().is_none());
+
+        // Nothing left to remove
+        history.remove_session(0);
+        assert_eq!(history.aggregate, AggregateStats::default());
+    }
+
+    #[test]
+    fn session_trend_from_measurements() {
+        let measurement = |timestamp: f64, wpm: f64| {
+            format!(
+                r#"{{ "timestamp": {timestamp}, "wpm_actual": {wpm}, "wpm_raw": {wpm},
+                    "accuracy_actual": 100.0, "accuracy_raw": 100.0,
+                    "consistency_actual_percent": 100.0, "ipm_actual": 0.0, "ipm_raw": 0.0 }}"#
+            )
+        };
+        let recorded = session(
+            2.0,
+            &[measurement(1.0, 50.0), measurement(2.0, 70.0)].join(","),
+        );
+
+        assert_eq!(
+            session_trend(&recorded, |m| m.wpm_actual, 60.0),
+            vec![(1.0, 50.0), (2.0, 70.0)]
+        );
+    }
+
+    #[test]
+    fn session_trend_without_measurements_is_flat() {
+        // Saved before the measurements were recorded
+        let legacy = session(30.0, "");
+
+        assert_eq!(
+            session_trend(&legacy, |m| m.wpm_actual, 60.0),
+            vec![(0.0, 60.0), (30.0, 60.0)]
+        );
+    }
+
+    #[test]
+    fn moving_average_over_window() {
+        let data = [(0.0, 10.0), (1.0, 20.0), (2.0, 30.0), (3.0, 40.0)];
+
+        let averaged = moving_average(&data, 2);
+
+        assert_eq!(
+            averaged,
+            vec![(0.0, 10.0), (1.0, 15.0), (2.0, 25.0), (3.0, 35.0)]
+        );
+    }
+
+    #[test]
+    fn moving_average_zero_window_is_identity() {
+        let data = [(0.0, 10.0), (1.0, 20.0)];
+
+        assert_eq!(moving_average(&data, 0), data.to_vec());
+    }
+}
@@ -1,14 +1,24 @@
-use std::{fmt::Display, thread::JoinHandle};
+use std::{
+    fmt::Display,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{Receiver, Sender, channel},
+    },
+    thread::JoinHandle,
+};
 
+use crossterm::event::{Event, KeyCode, KeyModifiers};
 use ratatui::{
     layout::{Alignment, Constraint},
+    style::Stylize,
     text::{Line, ToSpan},
     widgets::{Block, Paragraph},
 };
 
 use crate::{
-    config::{Config, theme::SpinnerState},
-    utils::{center, centered_padding},
+    config::{Config, keymap::Action, theme::SpinnerState},
+    utils::{center, centered_padding, fade, height_of_lines},
 };
 
 use super::Message;
@@ -25,30 +35,65 @@ impl Display for LoadError {
     }
 }
 
+/// Handle given to the closure of a [`Loading`] page, to post status updates and check for
+/// cancellation
+pub struct Task {
+    progress: Sender<String>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Task {
+    /// Posts a status update, shown below the message of the loading screen
+    pub fn status(&self, status: impl Into<String>) {
+        // Sending only fails if the loading screen is already gone
+        let _ = self.progress.send(status.into());
+    }
+
+    /// Set when the user cancels the loading
+    pub fn cancelled(&self) -> &AtomicBool {
+        &self.cancelled
+    }
+}
+
 /// Page: Loading
 pub struct Loading {
     /// The handle of the underlying thread
     handle: Option<JoinHandle<Result<Message, LoadError>>>,
     message: String,
+    /// Status updates posted by the underlying thread
+    progress: Receiver<String>,
+    /// The latest status update
+    status: Option<String>,
+    cancelled: Arc<AtomicBool>,
     spinner_state: SpinnerState,
 }
 
 impl Loading {
     /// Creats a new `LoadingScreen`.
     ///
-    /// * `F`: The closure to run in the background
+    /// * `F`: The closure to run in the background. It can post status updates and check for
+    ///   cancellation through the given [`Task`]
     /// * `E`: The error type returned by the closure (`F`)
     pub fn load<F, E>(config: &Config, message: &str, func: F) -> Self
     where
-        F: FnOnce(&Config) -> Result<Message, E> + Send + 'static,
+        F: FnOnce(&Config, Task) -> Result<Message, E> + Send + 'static,
         E: std::error::Error + Send + 'static,
     {
         let config_clone = config.clone();
-        let wrapper = move || func(&config_clone).map_err(|e| LoadError(e.to_string()));
+        let (sender, progress) = channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let task = Task {
+            progress: sender,
+            cancelled: cancelled.clone(),
+        };
+        let wrapper = move || func(&config_clone, task).map_err(|e| LoadError(e.to_string()));
         Self {
             handle: Some(std::thread::spawn(wrapper)),
             spinner_state: config.settings.theme.spinner.make_state(),
             message: message.to_string(),
+            progress,
+            status: None,
+            cancelled,
         }
     }
 
@@ -88,19 +133,62 @@ impl Loading {
             .theme
             .spinner
             .render(&mut self.spinner_state);
-        let text = Line::from(vec![spinner, self.message.to_span()]);
-        let height = (text.width() as u16).div_ceil(area.width);
+        let mut lines = vec![Line::from(vec![spinner, self.message.to_span()])];
+        if let Some(status) = &self.status {
+            lines.push(Line::from(status.as_str()).fg(fade(
+                config.settings.theme.term_fg,
+                config.settings.theme.term_bg,
+                0.5,
+                false,
+            )));
+        }
+        let height = height_of_lines(&lines, area);
 
-        let text = Paragraph::new(text)
+        let text = Paragraph::new(lines)
             .alignment(Alignment::Center)
             .block(Block::new().padding(centered_padding(area, Some(height), None)));
 
         frame.render_widget(text, area);
     }
 
+    pub fn render_top(&self, config: &Config) -> Option<Line<'_>> {
+        Some(Line::from(
+            config.tr_with(
+                "loading.cancel",
+                &[(
+                    "key",
+                    &config
+                        .settings
+                        .keybindings
+                        .hint(Action::PreviousPage)
+                        .to_uppercase(),
+                )],
+            ),
+        ))
+    }
+
+    pub fn handle_events(&self, event: &Event, config: &Config) -> Option<Message> {
+        let keys = &config.settings.keybindings;
+        if let Event::Key(key) = event
+            && key.is_press()
+            && (keys.is(Action::PreviousPage, key)
+                || (key.code == KeyCode::Char('c') && key.modifiers == KeyModifiers::CONTROL))
+        {
+            // The thread is left to finish on its own, and its result is dropped with the page
+            self.cancelled.store(true, Ordering::Relaxed);
+            return Some(Message::Pop);
+        }
+
+        None
+    }
+
     pub fn poll(&mut self, _config: &Config) -> Option<Message> {
         self.spinner_state.tick();
 
+        if let Some(status) = self.progress.try_iter().last() {
+            self.status = Some(status);
+        }
+
         if !self.is_finished() {
             return None;
         }
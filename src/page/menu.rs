@@ -1,23 +1,29 @@
-use std::fmt::Display;
+use std::{fmt::Display, thread::JoinHandle};
 
-use super::{History, Message, loadscreen::Loading, session::Session};
+use web_time::SystemTime;
+
+use super::{History, Message, has_command_modifier, loadscreen::Loading, session::Session};
 
 use crossterm::event::{Event, KeyCode, KeyEvent};
 use derive_more::From;
 use ratatui::{
-    layout::{Constraint, Rect},
+    layout::{Constraint, Layout, Rect},
     style::{Style, Stylize},
-    text::{Line, Span},
-    widgets::{Block, List, block::Title},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, List, Padding, Paragraph, Wrap, block::Title},
 };
 use thiserror::Error;
 
 use crate::{
     config::{
         Config, ModeConfig, SourceConfig,
-        parameters::{Definition, Parameter},
+        keymap::Action,
+        parameters::{Definition, Parameter, ParameterError, ParameterValues},
+    },
+    page::session::{
+        Conditions, CreateModeError, FetchError, Mode, Source, combined_name, session_seed,
     },
-    page::session::{CreateModeError, FetchError, Mode},
+    statistics::local_day,
     utils::{center, centered_padding},
 };
 
@@ -48,17 +54,73 @@ enum State {
     ParameterConfig,
 }
 
+/// The amount of words shown in the source preview
+const PREVIEW_WORDS: usize = 50;
+
+/// Preview of a source's output, fetched in a background thread
+#[derive(Debug)]
+enum Preview {
+    Loading(JoinHandle<Result<String, String>>),
+    Text(String),
+    Error(String),
+}
+
+impl Preview {
+    /// Spawns a thread fetching the output of `sources` with the given parameters and seed
+    fn load(
+        config: &Config,
+        sources: Vec<SourceConfig>,
+        parameters: ParameterValues,
+        seed: u64,
+    ) -> Self {
+        let config = config.clone();
+        Self::Loading(std::thread::spawn(move || {
+            let parameters = parameters.with_seed(seed);
+            let mut source = Source::from_configs(&config, sources, &parameters, seed)
+                .map_err(|error| error.to_string())?;
+            let text = source.fetch().map_err(|error| error.to_string())?;
+            Ok(text
+                .split_whitespace()
+                .take(PREVIEW_WORDS)
+                .collect::<Vec<_>>()
+                .join(" "))
+        }))
+    }
+
+    /// Joins the underlying thread if it is finished
+    fn poll(self) -> Self {
+        match self {
+            Self::Loading(handle) if handle.is_finished() => match handle.join() {
+                Ok(Ok(text)) => Self::Text(text),
+                Ok(Err(error)) => Self::Error(error),
+                Err(_) => Self::Error("Failed to join threadhandle".to_string()),
+            },
+            preview => preview,
+        }
+    }
+}
+
 #[derive(Debug)]
 struct Context {
     modes: Vec<ModeConfig>,
     sources: Vec<SourceConfig>,
     selected_mode: Option<Box<ModeConfig>>,
-    selected_source: Option<Box<SourceConfig>>,
+    selected_sources: Vec<SourceConfig>,
+    /// Names of the sources marked for combining in the source selection
+    marked_sources: Vec<String>,
     parameters: Vec<(String, Parameter)>,
     main_index: usize,
     mode_index: usize,
     source_index: usize,
     param_index: usize,
+    /// Search filter for the mode and source lists. `None` when not searching
+    filter: Option<String>,
+    /// Preview of the selected source's output in the parameter config
+    preview: Option<Preview>,
+    /// Why the parameters can't be used for a session, if they can't
+    parameter_error: Option<InvalidParameters>,
+    /// Current daily streak of saved sessions
+    streak: u32,
 }
 
 impl Context {
@@ -73,18 +135,148 @@ impl Context {
             return Err(ContextError::NoSources);
         }
 
+        let streak = config
+            .statistics_manager
+            .as_ref()
+            .and_then(|manager| manager.streak_tracker().load().ok())
+            .map_or(0, |streak| streak.current(local_day(SystemTime::now())));
+
         Ok(Self {
             modes,
             sources,
             selected_mode: None,
-            selected_source: None,
+            selected_sources: Vec::new(),
+            marked_sources: Vec::new(),
             parameters: vec![],
             main_index: 0,
             mode_index: 0,
             source_index: 0,
             param_index: 0,
+            filter: None,
+            preview: None,
+            parameter_error: None,
+            streak,
         })
     }
+
+    /// Case-insensitive substring match against the active filter
+    fn matches_filter(&self, name: &str) -> bool {
+        self.filter
+            .as_ref()
+            .is_none_or(|filter| name.to_lowercase().contains(&filter.to_lowercase()))
+    }
+
+    fn filtered_modes(&self) -> Vec<&ModeConfig> {
+        self.modes
+            .iter()
+            .filter(|mode| self.matches_filter(&mode.meta.name))
+            .collect()
+    }
+
+    fn filtered_sources(&self) -> Vec<&SourceConfig> {
+        self.sources
+            .iter()
+            .filter(|source| self.matches_filter(&source.meta.name))
+            .collect()
+    }
+
+    /// Parameters of the given sources and the selected mode. Parameters with the same name are
+    /// shared, and the first definition is used
+    fn collect_parameters(
+        &self,
+        sources: &[SourceConfig],
+    ) -> Result<Vec<(String, Parameter)>, ParameterError> {
+        let mode = self.selected_mode.as_ref().unwrap();
+        let mut parameters: Vec<(String, Parameter)> = Vec::new();
+
+        for source in sources {
+            let source_overrides = mode.overrides.get(&source.meta.name);
+
+            for (name, definition) in source.parameters.iter().chain(mode.parameters.iter()) {
+                if parameters.iter().any(|(existing, _)| existing == name) {
+                    continue;
+                }
+
+                let mut definition = definition.clone();
+                let mut mutable = true;
+                if let Some(overrides) = source_overrides
+                    && let Some(override_param) = overrides.get(name)
+                {
+                    mutable = false;
+                    definition = Definition::FixedString(override_param.clone());
+                }
+
+                parameters.push((name.clone(), definition.into_parameter(mutable)?));
+            }
+        }
+
+        Ok(parameters)
+    }
+
+    /// Indices of the parameters shown in the parameter config: Those that can be changed, and
+    /// whose dependency is met
+    fn visible_parameters(&self) -> Vec<usize> {
+        self.parameters
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, parameter))| {
+                parameter.is_mutable()
+                    && parameter.dependency().is_none_or(|dependency| {
+                        self.parameters.iter().any(|(name, other)| {
+                            *name == dependency.parameter && other.get_value() == dependency.value
+                        })
+                    })
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// The highlighted parameter in the parameter config
+    fn selected_parameter(&self) -> Option<usize> {
+        self.visible_parameters().get(self.param_index).copied()
+    }
+
+    /// Checks that the parameters resolve to valid conditions of the selected mode, so bad
+    /// values are reported before loading the session
+    fn validate_parameters(&mut self) {
+        let mode = self.selected_mode.as_ref().unwrap();
+        // The seed itself doesn't matter, only that `{seed}` resolves to a number
+        let parameters = self
+            .parameters
+            .iter()
+            .cloned()
+            .collect::<ParameterValues>()
+            .with_seed(0);
+
+        self.parameter_error = Conditions::from_config(mode.conditions.clone(), &parameters)
+            .err()
+            .map(|error| {
+                // The error is shown for the parameter used by the failing condition
+                let value = match &error {
+                    CreateModeError::Condition(error) => mode.conditions.value(error.condition()),
+                    _ => None,
+                };
+                let parameter = value.and_then(|value| {
+                    self.parameters
+                        .iter()
+                        .map(|(name, _)| name)
+                        .find(|name| value.references(name))
+                        .cloned()
+                });
+                InvalidParameters {
+                    parameter,
+                    message: error.to_string(),
+                }
+            });
+    }
+
+    /// Title of a filterable list
+    fn filter_title(&self, config: &Config, title: &str) -> String {
+        self.filter.as_ref().map_or_else(
+            || config.tr_with("menu.search_hint", &[("title", &title)]),
+            |filter| config.tr_with("menu.searching", &[("title", &title), ("filter", filter)]),
+        )
+    }
 }
 
 #[derive(Debug)]
@@ -129,7 +321,16 @@ impl Menu {
         }
     }
 
+    pub fn render_top(&self, config: &Config) -> Option<Line<'_>> {
+        matches!(self.state, State::MainMenu)
+            .then(|| Line::raw(config.tr("menu.paste_hint").to_string()))
+    }
+
     pub fn handle_events(&mut self, event: &Event, config: &Config) -> Option<Message> {
+        if let Event::Paste(text) = event {
+            return create_pasted_session(config, text);
+        }
+
         if let Event::Key(key) = event
             && key.is_press()
         {
@@ -143,6 +344,25 @@ impl Menu {
 
         None
     }
+
+    /// Whether a text parameter is selected, which typed characters go to
+    pub fn is_editing_text(&self) -> bool {
+        matches!(self.state, State::ParameterConfig)
+            && self
+                .context
+                .selected_parameter()
+                .is_some_and(|index| self.context.parameters[index].1.is_text())
+    }
+
+    /// Whether typed characters go to a text parameter or the search filter
+    pub fn is_typing(&self) -> bool {
+        self.is_editing_text() || self.context.filter.is_some()
+    }
+
+    pub fn poll(&mut self, _config: &Config) -> Option<Message> {
+        self.context.preview = self.context.preview.take().map(Preview::poll);
+        None
+    }
 }
 
 // Render helpers
@@ -153,13 +373,20 @@ impl Menu {
         area: ratatui::prelude::Rect,
         config: &Config,
     ) {
-        let main_menu_items = ["Start Typing Session", "View Statistics History"];
+        let main_menu_items = [
+            config.tr("menu.start_session"),
+            config.tr("menu.view_history"),
+        ];
         let index = self.context.main_index;
+        let title = match self.context.streak {
+            0 => config.tr("menu.main_menu").to_string(),
+            days => config.tr_with("menu.streak", &[("days", &days)]),
+        };
         render_list(
             config,
             frame,
             main_menu_items.iter(),
-            "Main Menu",
+            title,
             area,
             index,
             false,
@@ -173,8 +400,11 @@ impl Menu {
         config: &Config,
     ) {
         let index = self.context.mode_index;
-        let items = self.context.modes.iter();
-        render_list(config, frame, items, "Select mode", area, index, false);
+        let items = self.context.filtered_modes().into_iter();
+        let title = self
+            .context
+            .filter_title(config, config.tr("menu.select_mode"));
+        render_list(config, frame, items, title, area, index, false);
     }
 
     fn render_source_select(
@@ -185,10 +415,19 @@ impl Menu {
     ) {
         let mode = self.context.selected_mode.as_ref().unwrap();
         let index = self.context.source_index;
-        let items = self.context.sources.iter();
+        let items = self
+            .context
+            .filtered_sources()
+            .into_iter()
+            .map(|source| Marked {
+                source,
+                marked: self.context.marked_sources.contains(&source.meta.name),
+            });
         let title = Line::from(vec![
-            Span::raw("Select Source for Mode "),
+            Span::raw(config.tr("menu.select_source")),
             Span::raw(&mode.meta.name).bold(),
+            Span::raw(config.tr("menu.combine_hint")),
+            Span::raw(self.context.filter_title(config, "")),
         ]);
         render_list(config, frame, items, title, area, index, false);
     }
@@ -200,37 +439,89 @@ impl Menu {
         config: &Config,
     ) {
         let mode = self.context.selected_mode.as_ref().unwrap();
-        let source = self.context.selected_source.as_ref().unwrap();
+        let source_name = combined_name(&self.context.selected_sources);
         let index = self.context.param_index;
 
+        let error = self.context.parameter_error.as_ref();
         let items = self
             .context
-            .parameters
-            .iter()
-            .filter(|(_, p)| p.is_mutable());
+            .visible_parameters()
+            .into_iter()
+            .map(|index| ParameterRow {
+                parameter: &self.context.parameters[index],
+                error: error
+                    .filter(|error| {
+                        error.parameter.as_ref() == Some(&self.context.parameters[index].0)
+                    })
+                    .map(|error| error.message.as_str()),
+            });
 
         let title = Line::from(vec![
-            Span::raw("Configuring Mode "),
+            Span::raw(config.tr("menu.configuring_mode")),
             Span::raw(&mode.meta.name).bold(),
-            Span::raw(" with Source "),
-            Span::raw(&source.meta.name).bold(),
+            Span::raw(config.tr("menu.with_source")),
+            Span::raw(source_name).bold(),
+            Span::raw(config.tr("menu.preview_hint")),
         ]);
 
-        render_list(config, frame, items, title, area, index, true)
+        let [mut list_area, preview_area] = if self.context.preview.is_some() {
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(area)
+        } else {
+            [area, Rect::default()]
+        };
+
+        // Errors that no shown parameter caused are shown below the list
+        if let Some(error) =
+            error.filter(|error| {
+                !self.context.visible_parameters().iter().any(|index| {
+                    error.parameter.as_ref() == Some(&self.context.parameters[*index].0)
+                })
+            })
+        {
+            let [parameters_area, error_area] =
+                Layout::vertical([Constraint::Fill(1), Constraint::Length(3)]).areas(list_area);
+            let error =
+                Paragraph::new(Line::raw(&error.message).fg(config.settings.theme.text.error))
+                    .wrap(Wrap { trim: false });
+            frame.render_widget(error, error_area);
+            list_area = parameters_area;
+        }
+
+        render_list(config, frame, items, title, list_area, index, true);
+
+        let Some(preview) = &self.context.preview else {
+            return;
+        };
+
+        let text = match preview {
+            Preview::Loading(_) => Line::raw(config.tr("menu.loading_preview")),
+            Preview::Text(text) => Line::raw(text),
+            Preview::Error(error) => Line::raw(error).fg(config.settings.theme.text.error),
+        };
+
+        let paragraph = Paragraph::new(text).wrap(Wrap { trim: false }).block(
+            Block::new()
+                .title(config.tr("menu.preview"))
+                .borders(Borders::LEFT)
+                .padding(Padding::horizontal(1)),
+        );
+
+        frame.render_widget(paragraph, preview_area);
     }
 }
 
 // Event handlers
 impl Menu {
     fn handle_main_menu(&mut self, key: &KeyEvent, config: &Config) -> Option<Message> {
+        let keys = &config.settings.keybindings;
         match key.code {
-            KeyCode::Up | KeyCode::Char('k') => {
+            _ if keys.is(Action::Up, key) => {
                 increment_index(&mut self.context.main_index, 2) // 2 items in main menu
             }
-            KeyCode::Down | KeyCode::Char('j') => {
+            _ if keys.is(Action::Down, key) => {
                 decrement_index(&mut self.context.main_index, 2) // 2 items in main menu
             }
-            KeyCode::Enter => {
+            _ if keys.is(Action::Select, key) => {
                 match self.context.main_index {
                     0 => {
                         // Start Typing Session
@@ -239,7 +530,7 @@ impl Menu {
                     1 => {
                         // View Statistics History
                         return match History::new(config) {
-                            Ok(history) => Some(Message::Show(history.into())),
+                            Ok(history) => Some(Message::Push(history.into())),
                             Err(error) => Some(Message::Error(Box::new(error))),
                         };
                     }
@@ -250,21 +541,56 @@ impl Menu {
         }
         None
     }
-    fn handle_mode_select(&mut self, key: &KeyEvent, _config: &Config) -> Option<Message> {
+    /// Handles keys editing the search filter. Returns true if the key was consumed
+    fn handle_filter_key(&mut self, key: &KeyEvent) -> bool {
+        let Some(filter) = &mut self.context.filter else {
+            if key.code == KeyCode::Char('/') {
+                self.context.filter = Some(String::new());
+                return true;
+            }
+            return false;
+        };
+
         match key.code {
-            KeyCode::Up | KeyCode::Char('k') => {
-                increment_index(&mut self.context.mode_index, self.context.modes.len())
+            // Commands like quitting are up to the app
+            KeyCode::Char(character) if !has_command_modifier(key) => filter.push(character),
+            KeyCode::Backspace => {
+                if filter.pop().is_none() {
+                    self.context.filter = None;
+                }
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                decrement_index(&mut self.context.mode_index, self.context.modes.len())
+            _ => return false,
+        }
+
+        // Keep the indices within the bounds of the filtered lists
+        self.context.mode_index = 0;
+        self.context.source_index = 0;
+        true
+    }
+
+    fn handle_mode_select(&mut self, key: &KeyEvent, config: &Config) -> Option<Message> {
+        let keys = &config.settings.keybindings;
+        if self.handle_filter_key(key) {
+            return None;
+        }
+
+        let modes_len = self.context.filtered_modes().len();
+        match key.code {
+            _ if modes_len > 0 && keys.is(Action::Up, key) => {
+                increment_index(&mut self.context.mode_index, modes_len)
+            }
+            _ if modes_len > 0 && keys.is(Action::Down, key) => {
+                decrement_index(&mut self.context.mode_index, modes_len)
             }
-            KeyCode::Enter => {
-                // SAFETY: The index is always within range of the `modes` Vec
-                let mode = self.context.modes[self.context.mode_index].clone();
+            _ if modes_len > 0 && keys.is(Action::Select, key) => {
+                // SAFETY: The index is always within range of the filtered modes
+                let mode = self.context.filtered_modes()[self.context.mode_index].clone();
                 self.context.selected_mode = Some(Box::new(mode));
+                self.context.filter = None;
                 self.state = State::SourceSelect;
             }
-            KeyCode::Backspace => {
+            _ if keys.is(Action::Back, key) || keys.is(Action::PreviousPage, key) => {
+                self.context.filter = None;
                 self.state = State::MainMenu;
             }
             _ => (),
@@ -274,49 +600,68 @@ impl Menu {
     }
 
     fn handle_source_select(&mut self, key: &KeyEvent, config: &Config) -> Option<Message> {
+        let keys = &config.settings.keybindings;
+        if self.handle_filter_key(key) {
+            return None;
+        }
+
+        let sources_len = self.context.filtered_sources().len();
         match key.code {
-            KeyCode::Up | KeyCode::Char('k') => {
-                increment_index(&mut self.context.source_index, self.context.sources.len())
+            _ if sources_len > 0 && keys.is(Action::Up, key) => {
+                increment_index(&mut self.context.source_index, sources_len)
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                decrement_index(&mut self.context.source_index, self.context.sources.len())
+            _ if sources_len > 0 && keys.is(Action::Down, key) => {
+                decrement_index(&mut self.context.source_index, sources_len)
             }
-            KeyCode::Enter => {
-                let source = self.context.sources[self.context.source_index].clone();
-                let mode = self.context.selected_mode.as_ref().unwrap();
-                let source_overrides = mode.overrides.get(&source.meta.name);
-
-                let mut parameters = Vec::new();
-
-                for (name, definition) in source.parameters.iter().chain(mode.parameters.iter()) {
-                    let mut definition = definition.clone();
-                    let mut mutable = true;
-                    if let Some(overrides) = source_overrides
-                        && let Some(override_param) = overrides.get(name)
-                    {
-                        mutable = false;
-                        definition = Definition::FixedString(override_param.clone());
-                    }
-
-                    let parameter = match definition.into_parameter(mutable) {
-                        Ok(p) => p,
-                        Err(error) => return Some(Message::Error(Box::new(error))),
-                    };
-
-                    parameters.push((name.clone(), parameter));
+            _ if sources_len > 0 && key.code == KeyCode::Char(' ') => {
+                let name = &self.context.filtered_sources()[self.context.source_index]
+                    .meta
+                    .name;
+                if let Some(position) = self.context.marked_sources.iter().position(|n| n == name) {
+                    self.context.marked_sources.remove(position);
+                } else {
+                    self.context.marked_sources.push(name.clone());
                 }
-
-                self.context.selected_source = Some(Box::new(source));
+            }
+            _ if sources_len > 0 && keys.is(Action::Select, key) => {
+                // Combine the marked sources, or use the highlighted one if none are marked
+                let sources = if self.context.marked_sources.is_empty() {
+                    vec![self.context.filtered_sources()[self.context.source_index].clone()]
+                } else {
+                    self.context
+                        .marked_sources
+                        .iter()
+                        .filter_map(|name| {
+                            self.context
+                                .sources
+                                .iter()
+                                .find(|source| &source.meta.name == name)
+                                .cloned()
+                        })
+                        .collect()
+                };
+                self.context.filter = None;
+
+                let parameters = match self.context.collect_parameters(&sources) {
+                    Ok(parameters) => parameters,
+                    Err(error) => return Some(Message::Error(Box::new(error))),
+                };
+
+                self.context.selected_sources = sources;
 
                 if parameters.is_empty() {
                     return self.create_session(config);
                 }
 
                 self.context.parameters = parameters;
+                self.context.param_index = 0;
+                self.context.validate_parameters();
                 self.state = State::ParameterConfig;
             }
-            KeyCode::Backspace => {
+            _ if keys.is(Action::Back, key) || keys.is(Action::PreviousPage, key) => {
                 self.context.selected_mode = None;
+                self.context.marked_sources.clear();
+                self.context.filter = None;
                 self.state = State::ModeSelect;
             }
             _ => (),
@@ -326,26 +671,35 @@ impl Menu {
     }
 
     fn handle_parameter_config(&mut self, key: &KeyEvent, config: &Config) -> Option<Message> {
+        let keys = &config.settings.keybindings;
+        if self.handle_text_key(key) {
+            return None;
+        }
+
+        let visible_len = self.context.visible_parameters().len();
         match key.code {
-            KeyCode::Up | KeyCode::Char('k') => {
-                increment_index(&mut self.context.param_index, self.context.parameters.len())
+            _ if visible_len > 0 && keys.is(Action::Up, key) => {
+                increment_index(&mut self.context.param_index, visible_len)
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                decrement_index(&mut self.context.param_index, self.context.parameters.len())
+            _ if visible_len > 0 && keys.is(Action::Down, key) => {
+                decrement_index(&mut self.context.param_index, visible_len)
             }
-            KeyCode::Right | KeyCode::Char('l') => self.context.parameters
-                [self.context.param_index]
-                .1
-                .increment(),
-            KeyCode::Left | KeyCode::Char('h') => self.context.parameters[self.context.param_index]
-                .1
-                .decrement(),
-            KeyCode::Enter => {
+            _ if keys.is(Action::Right, key) => self.change_parameter(Parameter::increment),
+            _ if keys.is(Action::Left, key) => self.change_parameter(Parameter::decrement),
+            _ if keys.is(Action::Preview, key) => {
+                let sources = self.context.selected_sources.clone();
+                let parameters = self.context.parameters.iter().cloned().collect();
+                let seed = session_seed(config, self.context.selected_mode.as_ref().unwrap());
+                self.context.preview = Some(Preview::load(config, sources, parameters, seed));
+            }
+            // Invalid parameters would only fail while loading the session
+            _ if keys.is(Action::Select, key) && self.context.parameter_error.is_none() => {
                 return self.create_session(config);
             }
-            KeyCode::Backspace => {
+            _ if keys.is(Action::Back, key) || keys.is(Action::PreviousPage, key) => {
                 // Go back to source selection
-                self.context.selected_source = None;
+                self.context.preview = None;
+                self.context.selected_sources.clear();
                 self.state = State::SourceSelect;
             }
             _ => (),
@@ -354,19 +708,75 @@ impl Menu {
         None
     }
 
+    /// Handles keys editing a selected text parameter. Returns true if the key was consumed
+    fn handle_text_key(&mut self, key: &KeyEvent) -> bool {
+        if !self.is_editing_text() {
+            return false;
+        }
+
+        match key.code {
+            KeyCode::Char(character) if !has_command_modifier(key) => {
+                self.change_parameter(|parameter| parameter.push(character));
+            }
+            KeyCode::Backspace => self.change_parameter(Parameter::pop),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Applies `change` to the highlighted parameter
+    fn change_parameter(&mut self, change: impl FnOnce(&mut Parameter)) {
+        let Some(selected) = self.context.selected_parameter() else {
+            return;
+        };
+        change(&mut self.context.parameters[selected].1);
+        self.context.validate_parameters();
+
+        // Parameters depending on the changed one may have appeared or disappeared
+        self.context.param_index = self
+            .context
+            .visible_parameters()
+            .iter()
+            .position(|index| *index == selected)
+            .unwrap_or(0);
+        // The preview no longer reflects the parameters
+        self.context.preview = None;
+    }
+
     fn create_session(&self, config: &Config) -> Option<Message> {
         let mode = *self.context.selected_mode.as_ref().unwrap().clone();
-        let source = *self.context.selected_source.as_ref().unwrap().clone();
+        let sources = self.context.selected_sources.clone();
         let parameters = self.context.parameters.iter().cloned().collect();
-        let session_loader = Loading::load(config, "Loading words...", move |config| {
-            let mode = Mode::from_config(config, mode, source, parameters).map_err(Box::new)?;
-            Session::new(config, mode)
+        let message = config.tr("menu.loading_words");
+        let session_loader = Loading::load(config, message, move |config, task| {
+            task.status(config.tr_with(
+                "menu.preparing_source",
+                &[("source", &combined_name(&sources))],
+            ));
+            let mode = Mode::from_config(config, mode, sources, parameters).map_err(Box::new)?;
+            task.status(config.tr("menu.waiting_for_source"));
+            Session::new_cancellable(config, mode, task.cancelled())
                 .map(|session| Message::Show(session.into()))
                 .map_err(CreateSessionError::from)
         });
 
-        Some(Message::Show(session_loader.into()))
+        // The menu stays below the loader, so cancelling returns to the parameters
+        Some(Message::Push(session_loader.into()))
+    }
+}
+
+/// Starts a session typing pasted text. Line breaks are kept, so pasted code keeps its layout
+fn create_pasted_session(config: &Config, text: &str) -> Option<Message> {
+    // Terminals commonly send line breaks in pastes as carriage returns
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    if text.trim().is_empty() {
+        return None;
     }
+
+    Some(match Session::new(config, Mode::from_text(text)) {
+        Ok(session) => Message::Show(session.into()),
+        Err(error) => Message::Error(Box::new(error)),
+    })
 }
 
 trait ListItem {
@@ -374,9 +784,13 @@ trait ListItem {
     fn description(&self) -> Option<String> {
         None
     }
+    /// Shown on a line below the item
+    fn error(&self) -> Option<&str> {
+        None
+    }
 }
 
-impl ListItem for &&'static str {
+impl ListItem for &&str {
     fn title(&self) -> impl Display {
         self
     }
@@ -402,13 +816,48 @@ impl ListItem for &SourceConfig {
     }
 }
 
-impl ListItem for &(String, Parameter) {
+/// A source in the source selection, which can be marked for combining
+struct Marked<'a> {
+    source: &'a SourceConfig,
+    marked: bool,
+}
+
+impl ListItem for Marked<'_> {
     fn title(&self) -> impl Display {
-        &self.0
+        let checkbox = if self.marked { "[x]" } else { "[ ]" };
+        format!("{checkbox} {}", self.source.meta.name)
     }
 
     fn description(&self) -> Option<String> {
-        Some(format!(": {}", self.1.get_value()))
+        self.source.description()
+    }
+}
+
+/// Why the parameters can't be used for a session
+#[derive(Debug)]
+struct InvalidParameters {
+    /// The name of the parameter that caused the error, if it's known
+    parameter: Option<String>,
+    message: String,
+}
+
+/// A parameter in the parameter config, with its error
+struct ParameterRow<'a> {
+    parameter: &'a (String, Parameter),
+    error: Option<&'a str>,
+}
+
+impl ListItem for ParameterRow<'_> {
+    fn title(&self) -> impl Display {
+        &self.parameter.0
+    }
+
+    fn description(&self) -> Option<String> {
+        Some(format!(": {}", self.parameter.1.get_value()))
+    }
+
+    fn error(&self) -> Option<&str> {
+        self.error
     }
 }
 
@@ -441,13 +890,25 @@ fn render_list<'a>(
 
         let selector = if is_selected { " > " } else { "   " };
 
-        Line::from(vec![Span::raw(selector), Span::styled(display, style)])
+        let mut text = Text::from(Line::from(vec![
+            Span::raw(selector),
+            Span::styled(display, style),
+        ]));
+        if let Some(error) = item.error() {
+            text.push_line(Line::from(vec![
+                Span::raw("   "),
+                Span::raw(error.to_string()).fg(config.settings.theme.text.error),
+            ]));
+        }
+        text
     });
+    let items = items.collect::<Vec<_>>();
+    let height = items.iter().map(Text::height).sum::<usize>();
     let list = List::new(items);
     let padding = centered_padding(
         area,
         // + 1 to account for title
-        Some(list.len() as u16 + 1),
+        Some(height as u16 + 1),
         None,
     );
     let area = Block::new().padding(padding).inner(area);
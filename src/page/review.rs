@@ -0,0 +1,247 @@
+use crossterm::event::Event;
+use gladius::{CharacterResult, State, TypingSession, render::LineRenderConfig, statistics::Input};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+};
+
+use crate::{
+    config::{Config, keymap::Action},
+    utils::{center, fade},
+};
+
+use super::Message;
+
+/// Page: Review
+///
+/// Shows the finished text of a session with its mistakes highlighted, or as a heatmap of how
+/// fast each character was typed.
+///
+#[derive(Debug)]
+pub struct Review {
+    gladius_session: TypingSession,
+    scroll: u16,
+    line_count: u16,
+    /// How slow each character was typed, from 0 (fastest) to 1 (slowest)
+    slowness: Vec<Option<f32>>,
+    heatmap: bool,
+}
+
+impl Review {
+    /// Creates a review of `gladius_session`
+    pub fn new(gladius_session: TypingSession) -> Self {
+        let latencies = character_latencies(&gladius_session.statistics().input_history);
+        Self {
+            gladius_session,
+            scroll: 0,
+            line_count: 0,
+            slowness: normalize(&latencies),
+            heatmap: false,
+        }
+    }
+}
+
+// Rendering logic
+impl Review {
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, config: &Config) {
+        let area = center(
+            area,
+            Constraint::Percentage(80),
+            Constraint::Percentage(100),
+        );
+        let theme = &config.settings.theme;
+        let untyped = fade(theme.term_fg, theme.term_bg, 0.5, false);
+
+        let lines = self.gladius_session.render_lines(
+            |line| {
+                let rendered = line
+                    .contents
+                    .iter()
+                    .map(|ctx| {
+                        let is_space = ctx.character.char == ' ';
+                        let mut style = match ctx.character.state {
+                            State::Correct => Style::new().fg(theme.text.success),
+                            State::Corrected => Style::new().fg(theme.text.warning),
+                            State::Wrong | State::WasWrong if is_space => {
+                                Style::new().bg(theme.text.error)
+                            }
+                            State::Wrong | State::WasWrong => Style::new().fg(theme.text.error),
+                            State::None | State::WasCorrect | State::WasCorrected => {
+                                Style::new().fg(untyped)
+                            }
+                        }
+                        .add_modifier(Modifier::BOLD)
+                        .add_modifier(theme.preset.modifiers(&ctx.character.state));
+
+                        if self.heatmap
+                            && let Some(Some(slowness)) = self.slowness.get(ctx.index)
+                        {
+                            style = style.fg(fade(
+                                theme.text.success,
+                                theme.text.error,
+                                *slowness,
+                                true,
+                            ));
+                        }
+
+                        if let Some(word) = ctx.word
+                            && word.state == State::Wrong
+                        {
+                            style = style.underlined().underline_color(theme.text.error);
+                        }
+
+                        Span::from(ctx.character.char.to_string()).style(style)
+                    })
+                    .collect::<Line>();
+
+                Some(rendered)
+            },
+            LineRenderConfig::new(area.width as usize).with_newline_breaking(true),
+        );
+
+        self.line_count = lines.len() as u16;
+        self.scroll = self.scroll.min(self.max_scroll(area.height));
+
+        let paragraph = Paragraph::new(lines)
+            .scroll((self.scroll, 0))
+            .block(Block::new());
+
+        frame.render_widget(paragraph, area);
+    }
+
+    pub fn render_top(&self, config: &Config) -> Option<Line<'_>> {
+        Some(Line::raw(config.tr("review.keys").to_string()))
+    }
+
+    pub fn handle_events(&mut self, event: &Event, config: &Config) -> Option<Message> {
+        let keys = &config.settings.keybindings;
+        if let Event::Key(key) = event
+            && key.is_press()
+        {
+            match key.code {
+                _ if keys.is(Action::Back, key) || keys.is(Action::Review, key) => {
+                    return Some(Message::Pop);
+                }
+                _ if keys.is(Action::Heatmap, key) => self.heatmap = !self.heatmap,
+                _ if keys.is(Action::Up, key) => self.scroll = self.scroll.saturating_sub(1),
+                _ if keys.is(Action::Down, key) => {
+                    // Clamped to the text height on the next render
+                    self.scroll = self.scroll.saturating_add(1);
+                }
+                _ => (),
+            }
+        }
+
+        None
+    }
+
+    /// The furthest the text can be scrolled while still filling `height`
+    const fn max_scroll(&self, height: u16) -> u16 {
+        self.line_count.saturating_sub(height)
+    }
+}
+
+/// The time each character in the text took to type, measured from the previous keystroke.
+///
+/// Retyped characters keep the latency of their last attempt. Characters that weren't typed, and
+/// the very first keystroke, have none.
+fn character_latencies(inputs: &[Input]) -> Vec<Option<f64>> {
+    let mut latencies = Vec::new();
+    let mut position = 0usize;
+    let mut previous: Option<f64> = None;
+
+    for input in inputs {
+        if let CharacterResult::Deleted(_) = input.result {
+            position = position.saturating_sub(1);
+        } else {
+            if latencies.len() <= position {
+                latencies.resize(position + 1, None);
+            }
+            latencies[position] = previous.map(|previous| input.timestamp - previous);
+            position += 1;
+        }
+        previous = Some(input.timestamp);
+    }
+
+    latencies.truncate(position);
+    latencies
+}
+
+/// Scales latencies between the fastest (0) and the slowest (1)
+fn normalize(latencies: &[Option<f64>]) -> Vec<Option<f32>> {
+    let (fastest, slowest) = latencies
+        .iter()
+        .flatten()
+        .fold((f64::MAX, f64::MIN), |(fastest, slowest), latency| {
+            (fastest.min(*latency), slowest.max(*latency))
+        });
+    let range = slowest - fastest;
+
+    latencies
+        .iter()
+        .map(|latency| {
+            latency.map(|latency| {
+                if range > 0.0 {
+                    ((latency - fastest) / range) as f32
+                } else {
+                    0.0
+                }
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use gladius::{CharacterResult, State, statistics::Input};
+
+    use super::{character_latencies, normalize};
+
+    fn input(timestamp: f64, result: CharacterResult) -> Input {
+        Input {
+            timestamp,
+            char: 'a',
+            result,
+        }
+    }
+
+    #[test]
+    fn retyped_characters_keep_their_last_latency() {
+        let inputs = [
+            input(1.0, CharacterResult::Correct),
+            input(1.5, CharacterResult::Wrong),
+            input(2.0, CharacterResult::Deleted(State::Wrong)),
+            input(2.2, CharacterResult::Correct),
+            input(3.2, CharacterResult::Correct),
+        ];
+
+        let latencies = character_latencies(&inputs);
+
+        assert_eq!(latencies.len(), 3);
+        assert_eq!(latencies[0], None);
+        assert!((latencies[1].unwrap() - 0.2).abs() < 1e-9);
+        assert!((latencies[2].unwrap() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn deleted_characters_have_no_latency() {
+        let inputs = [
+            input(1.0, CharacterResult::Correct),
+            input(1.5, CharacterResult::Correct),
+            input(2.0, CharacterResult::Deleted(State::Correct)),
+        ];
+
+        assert_eq!(character_latencies(&inputs), [None]);
+    }
+
+    #[test]
+    fn normalize_scales_between_fastest_and_slowest() {
+        let slowness = normalize(&[None, Some(0.2), Some(0.6), Some(1.0)]);
+
+        assert_eq!(slowness, [None, Some(0.0), Some(0.5), Some(1.0)]);
+        assert_eq!(normalize(&[Some(0.3), Some(0.3)]), [Some(0.0), Some(0.0)]);
+    }
+}
@@ -1,31 +1,62 @@
-use std::ops::Rem;
+use std::{
+    collections::{HashSet, VecDeque},
+    io::{Write, stdout},
+    ops::Rem,
+    sync::atomic::AtomicBool,
+    time::{Duration, Instant},
+};
 
 use crossterm::event::{Event, KeyCode};
 use derive_more::Display;
-use gladius::{State, TypingSession, render::LineRenderConfig};
+use gladius::{
+    CharacterResult, State, TypingSession, Word, math::Accuracy, render::LineRenderConfig,
+    statistics::Statistics,
+};
 use ratatui::{
     Frame,
     layout::{Constraint, Layout, Rect},
     prelude::Color,
     style::{Modifier, Style, Stylize},
     text::{Line, Span},
-    widgets::{Block, Gauge, Paragraph, Wrap},
+    widgets::{Block, Gauge, Padding, Paragraph, Wrap},
 };
 
 use crate::{
-    config::Config,
+    config::{
+        Config,
+        keymap::Action,
+        session::GaugeKind,
+        stats::StatisticsConfig,
+        theme::{CaretStyle, TextTheme},
+    },
     page::{self},
-    utils::{center, centered_padding, fade, height_of_lines},
+    statistics::{EndReason, SerializableStatistics, SessionConfig, SessionExport},
+    utils::{ROUNDED_BLOCK, center, centered_padding, fade, height_of_lines},
 };
 
 mod mode;
 
-pub use mode::{CreateModeError, FetchError, Mode};
+pub use mode::{
+    Conditions, CreateModeError, FetchError, Mode, Source, combined_name, session_seed,
+};
 
 use super::Message;
 
 const MIN_GAUGE_HEIGHT: u16 = 1;
 const MAX_GAUGE_HEIGHT: u16 = 3;
+/// Wpm at which the Wpm gauge is full
+const WPM_GAUGE_MAX: f64 = 150.0;
+/// Measurements needed before the consistency is shown
+const MIN_CONSISTENCY_MEASUREMENTS: usize = 3;
+/// Consistency (in percent) from which it's shown as good, or at least as okay
+const GOOD_CONSISTENCY: f64 = 80.0;
+const OKAY_CONSISTENCY: f64 = 60.0;
+/// Shown in place of characters hidden by `blind_ahead`
+const MASK: char = '·';
+/// Minimum time between two error bells, so a burst of errors doesn't spam
+const BELL_DEBOUNCE: Duration = Duration::from_millis(500);
+/// Width of the panel listing the latest mistakes
+const MISTAKES_PANEL_WIDTH: u16 = 16;
 
 /// Page: TypingSession
 #[derive(Debug)]
@@ -33,12 +64,51 @@ pub struct Session {
     gladius_session: TypingSession,
     fetch_buffer: Option<String>,
     mode: Mode,
+    last_bell: Option<Instant>,
+    /// Time of the last keystroke
+    last_input: Option<Instant>,
+    /// Positions where a mistake was made, which reveal their character until it's corrected
+    revealed: HashSet<usize>,
+    /// Highest progress shown on the words gauge, in percent
+    words_progress: u16,
+    /// The latest mistakes, newest first
+    recent_mistakes: VecDeque<Mistake>,
+    /// Show only the text
+    minimal: bool,
+    /// Cells taken by the widest character of the text
+    widest_char: usize,
+}
+
+/// A wrong character, and the character that was expected instead
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Mistake {
+    expected: char,
+    typed: char,
 }
 
 impl Session {
     /// Creates a new `TypingSession`
-    pub fn new(_config: &Config, mut mode: Mode) -> Result<Self, FetchError> {
-        let text = mode.source.fetch()?;
+    pub fn new(config: &Config, mode: Mode) -> Result<Self, FetchError> {
+        Self::new_cancellable(config, mode, &AtomicBool::new(false))
+    }
+
+    /// Whether anything was typed yet
+    pub const fn is_busy(&self) -> bool {
+        self.last_input.is_some()
+    }
+
+    /// Whether only the text is shown
+    pub const fn is_minimal(&self) -> bool {
+        self.minimal
+    }
+
+    /// Creates a new `TypingSession`, giving up on fetching the text once `cancelled` is set
+    pub fn new_cancellable(
+        config: &Config,
+        mut mode: Mode,
+        cancelled: &AtomicBool,
+    ) -> Result<Self, FetchError> {
+        let text = mode.source.fetch_cancellable(cancelled)?;
         // Safety: Sources already check for empty output - This is the only error that can happen
         // when initializing a TypingSession
         let gladius_session = TypingSession::new(&text).expect("Failed to create TypingSession");
@@ -47,6 +117,13 @@ impl Session {
             gladius_session,
             fetch_buffer: None,
             mode,
+            last_bell: None,
+            last_input: None,
+            revealed: HashSet::new(),
+            words_progress: 0,
+            recent_mistakes: VecDeque::new(),
+            minimal: config.settings.session.minimal,
+            widest_char: widest_char(&text),
         })
     }
 }
@@ -66,30 +143,113 @@ impl Session {
                     ));
                 }
             } else if let Some(text) = self.fetch_buffer.take() {
+                self.widest_char = self.widest_char.max(widest_char(&text));
                 self.gladius_session.push_string(&format!(" {text}"));
             }
         }
         Ok(())
     }
 
-    fn should_end(&self) -> bool {
-        if self.gladius_session.is_fully_typed() {
-            return true;
+    /// Rings the terminal bell, unless it was rung very recently
+    fn ring_bell(&mut self) {
+        if self
+            .last_bell
+            .is_some_and(|last| last.elapsed() < BELL_DEBOUNCE)
+        {
+            return;
+        }
+
+        let mut stdout = stdout();
+        // The bell is a nicety - Failing to ring it shouldn't interrupt the session
+        let _ = stdout.write_all(b"\x07").and_then(|()| stdout.flush());
+        self.last_bell = Some(Instant::now());
+    }
+
+    /// Whether new characters are blocked until the last mistake has been fixed, which happens
+    /// once the running accuracy has dropped below `fix_below_accuracy`
+    fn is_blocked(&self) -> bool {
+        let conditions = &self.mode.conditions;
+        // Without deletions the user could never fix the mistake
+        if !conditions.require_fix_before_continue || !conditions.allow_deletions {
+            return false;
         }
 
-        if let Some(target) = self.mode.conditions.words_typed {
-            return self.gladius_session.words_typed_count() == target;
+        let last_is_wrong = self
+            .gladius_session
+            .input_len()
+            .checked_sub(1)
+            .and_then(|index| self.gladius_session.get_character(index))
+            .is_some_and(|character| character.state == State::Wrong);
+        if !last_is_wrong {
+            return false;
         }
 
-        if let Some(max_time) = self.mode.conditions.time {
-            return self.gladius_session.time_elapsed() > max_time.as_secs_f64();
+        let counters = &self.gladius_session.statistics().counters;
+        let accuracy = Accuracy::calculate(counters.adds, counters.errors, counters.corrections);
+        accuracy.actual < conditions.fix_below_accuracy as f64
+    }
+
+    /// Whether the idle timeout has passed since the last keystroke. Sessions that haven't been
+    /// started yet are never idle
+    fn is_idle(&self, config: &Config) -> bool {
+        config
+            .settings
+            .idle_timeout_seconds
+            .zip(self.last_input)
+            .is_some_and(|(timeout, last)| last.elapsed() >= Duration::from_secs(timeout))
+    }
+
+    /// Why the session should end, if it should
+    pub fn end_reason(&self, config: &Config) -> Option<EndReason> {
+        if self.is_idle(config) {
+            return Some(EndReason::Idle);
         }
 
-        if !self.mode.conditions.allow_errors {
-            return self.gladius_session.statistics().counters.errors > 0;
+        if self.gladius_session.is_fully_typed() {
+            return Some(EndReason::Finished);
         }
 
-        false
+        if !self.mode.conditions.allow_errors
+            && self.gladius_session.statistics().counters.errors > 0
+        {
+            return Some(EndReason::ErrorStop);
+        }
+
+        let time_elapsed = self.gladius_session.time_elapsed();
+        let conditions = &self.mode.conditions;
+        conditions
+            .goals_met(time_elapsed, self.gladius_session.words_typed_count())
+            .then(|| {
+                // With both goals required, the time limit is the one reported
+                if conditions
+                    .time
+                    .is_some_and(|max| time_elapsed > max.as_secs_f64())
+                {
+                    EndReason::TimeLimit
+                } else {
+                    EndReason::WordLimit
+                }
+            })
+    }
+}
+
+#[cfg(feature = "discord")]
+impl Session {
+    /// Mode name, live WPM and elapsed seconds, as shown in the Discord presence
+    pub fn presence(&self) -> (&str, Option<f64>, f64) {
+        let wpm = self
+            .gladius_session
+            .statistics()
+            .measurements
+            .last()
+            .map(|measure| measure.wpm.actual)
+            .filter(|_| self.mode.conditions.show_speed);
+
+        (
+            &self.mode.mode_name,
+            wpm,
+            self.gladius_session.time_elapsed(),
+        )
     }
 }
 
@@ -97,14 +257,30 @@ impl Session {
 impl Session {
     pub fn render(&self, frame: &mut Frame, area: Rect, config: &Config) {
         let mut cursor_position: Option<(u16, u16)> = None;
+        let mut end_of_text: Option<(u16, u16)> = None;
         let mut current_line = 0u16;
 
-        let [_, text_area, gauges_area] = Layout::vertical([
-            Constraint::Percentage(20),
-            Constraint::Percentage(60),
-            Constraint::Percentage(20),
-        ])
-        .areas(area);
+        let [_, text_area, gauges_area] = if self.minimal {
+            [Rect::default(), area, Rect::default()]
+        } else {
+            Layout::vertical([
+                Constraint::Percentage(20),
+                Constraint::Percentage(60),
+                Constraint::Percentage(20),
+            ])
+            .areas(area)
+        };
+        let text_area = if config.settings.recent_errors.is_some() && !self.minimal {
+            let [text_area, mistakes_area] = Layout::horizontal([
+                Constraint::Fill(1),
+                Constraint::Length(MISTAKES_PANEL_WIDTH),
+            ])
+            .areas(text_area);
+            self.render_mistakes(config, frame, mistakes_area);
+            text_area
+        } else {
+            text_area
+        };
         let text_area = center(
             text_area,
             Constraint::Percentage(80),
@@ -112,17 +288,26 @@ impl Session {
         );
 
         let mut longest_line = 0;
+        let input_len = self.gladius_session.input_len();
+        let caret_cell = config.settings.theme.cursor.style == CaretStyle::Cell;
+        let newline_glyph = config.settings.session.newline_glyph;
+        // The glyph takes a cell at the end of a line, which the wrapping has to leave room for
+        let wrap_width = text_area
+            .width
+            .saturating_sub(u16::from(newline_glyph.is_some()));
         let lines = self.gladius_session.render_lines(
             |line| {
-                let relative_idx = line.active_line_offset.unsigned_abs();
-                if relative_idx > config.settings.show_ghost_lines {
+                if !is_line_visible(line.active_line_offset, config) {
                     return None;
                 }
-
-                longest_line = longest_line.max(line.contents.len());
+                let relative_idx = line
+                    .active_line_offset
+                    .unsigned_abs()
+                    .min(config.settings.show_ghost_lines);
 
                 let (success, warning, error, foreground) =
                     create_line_text_colors(relative_idx, config);
+                let theme = &config.settings.theme;
 
                 let mut current_col = 0u16;
                 let rendered = line
@@ -132,7 +317,15 @@ impl Session {
                         let mut style = Style::new().fg(foreground);
                         let is_space = ctx.character.char == ' ';
 
-                        style = match ctx.character.state {
+                        let delayed =
+                            is_feedback_delayed(&self.mode.conditions, ctx.index, input_len);
+                        let state = if delayed {
+                            State::None
+                        } else {
+                            ctx.character.state
+                        };
+
+                        style = match state {
                             State::Correct => style.fg(success),
                             State::Corrected => style.fg(warning),
                             State::Wrong => {
@@ -144,9 +337,12 @@ impl Session {
                             }
                             _ => style,
                         }
-                        .add_modifier(Modifier::BOLD);
+                        .add_modifier(Modifier::BOLD)
+                        .add_modifier(theme.preset.modifiers(&state));
 
+                        // The underline would give away a mistake that is still withheld
                         if let Some(word) = ctx.word
+                            && !is_word_feedback_delayed(&self.mode.conditions, word, input_len)
                             && word.state == State::Wrong
                         {
                             style = style.underlined().underline_color(error);
@@ -155,25 +351,59 @@ impl Session {
                         if ctx.has_cursor {
                             // Position cursor at the current character
                             cursor_position = Some((current_col, current_line));
+                            if caret_cell {
+                                style = style.fg(theme.cursor.text).bg(theme.cursor.color);
+                            }
                         }
 
-                        let span = Span::from(ctx.character.char.to_string()).style(style);
-                        current_col += 1;
+                        let hidden =
+                            is_hidden(&self.mode.conditions, ctx.index, input_len, &self.revealed);
+                        if self.mode.conditions.reveal_on_error && ctx.index == input_len && !hidden
+                        {
+                            // Hint at the expected character
+                            style = style.fg(fade(foreground, theme.term_bg, 0.5, true));
+                        }
+
+                        if ctx.character.char == '\n'
+                            && newline_glyph.is_some()
+                            && state != State::Wrong
+                        {
+                            style = style.fg(fade(foreground, theme.term_bg, 0.5, true));
+                        }
+
+                        let span =
+                            Span::from(display_char(ctx.character.char, hidden, newline_glyph))
+                                .style(style);
+                        // Wide characters (CJK, emoji) occupy more than one terminal cell
+                        current_col += span.width() as u16;
                         span
                     })
                     .collect::<Line>();
 
+                longest_line = longest_line.max(rendered.width());
+                end_of_text = Some((current_col, current_line));
+
                 current_line += 1;
                 Some(rendered)
             },
-            LineRenderConfig::new(text_area.width as usize).with_newline_breaking(true),
+            LineRenderConfig::new(wrap_length(wrap_width, self.widest_char))
+                .with_newline_breaking(true),
         );
 
         let height = height_of_lines(&lines, text_area);
         let padding = centered_padding(text_area, Some(height), Some(longest_line as u16));
 
-        // Set cursor position if we found one
-        if let Some((cursor_x, cursor_y)) = cursor_position {
+        // When the text is fully typed no character carries the cursor, so we place it one cell
+        // past the last character instead
+        if cursor_position.is_none() && self.gladius_session.is_fully_typed() {
+            cursor_position = end_of_text;
+        }
+
+        // Set cursor position if we found one. The terminal cursor stays hidden when the caret is
+        // drawn as a cell
+        if let Some((cursor_x, cursor_y)) = cursor_position
+            && !caret_cell
+        {
             let cursor_area_x = text_area.x + padding.left + cursor_x;
             let cursor_area_y = text_area.y + padding.top + cursor_y;
             frame.set_cursor_position((cursor_area_x, cursor_area_y));
@@ -188,42 +418,36 @@ impl Session {
         self.render_gauges(config, frame, gauges_area);
     }
 
-    pub fn render_gauges(&self, config: &Config, frame: &mut Frame, area: Rect) {
-        let gauges = [
-            self.mode.conditions.time.as_ref().map(|max| {
-                let max = max.as_secs_f64();
-                let elapsed = self.gladius_session.time_elapsed();
-
-                let ratio = (elapsed / max).clamp(0.0, 1.0);
-                let percent = (ratio * 100.0).round() as u16;
-
-                let fg = match percent {
-                    60..=80 => config.settings.theme.text.warning,
-                    81..=100 => config.settings.theme.text.error,
-                    _ => config.settings.theme.text.success,
-                };
-
-                Gauge::default()
-                    .label(format!(
-                        "Time: {}/{}",
-                        format_time(elapsed),
-                        format_time(max)
-                    ))
-                    .percent(percent)
-                    .gauge_style(fg)
-            }),
-            self.mode.conditions.words_typed.as_ref().map(|goal| {
-                let words_typed = self.gladius_session.words_typed_count();
-                let percent = (words_typed.saturating_mul(100) + goal / 2) / goal;
+    /// Lists the latest mistakes as "expected → typed"
+    fn render_mistakes(&self, config: &Config, frame: &mut Frame, area: Rect) {
+        let lines = self
+            .recent_mistakes
+            .iter()
+            .map(|mistake| {
+                Line::from(vec![
+                    Span::raw(visible_char(mistake.expected)).bold(),
+                    Span::raw(" → "),
+                    Span::raw(visible_char(mistake.typed)).fg(config.settings.theme.text.error),
+                ])
+            })
+            .collect::<Vec<_>>();
 
-                Gauge::default()
-                    .label(format!("Words: {words_typed}/{goal}"))
-                    .percent(percent.clamp(0, 100) as u16)
-                    .gauge_style(config.settings.theme.text.highlight)
-            }),
-        ];
+        let panel = Paragraph::new(lines).block(
+            ROUNDED_BLOCK
+                .title(Span::from(config.tr("session.mistakes")).bold())
+                .padding(Padding::horizontal(1)),
+        );
+        frame.render_widget(panel, area);
+    }
 
-        let to_render = gauges.into_iter().flatten().collect::<Vec<_>>();
+    pub fn render_gauges(&self, config: &Config, frame: &mut Frame, area: Rect) {
+        let to_render = config
+            .settings
+            .session
+            .gauges
+            .iter()
+            .filter_map(|kind| self.gauge(*kind, config))
+            .collect::<Vec<_>>();
         if to_render.is_empty() {
             return;
         }
@@ -236,45 +460,220 @@ impl Session {
         }
     }
 
-    pub fn render_top(&self, _config: &Config) -> Option<Line<'_>> {
-        let time = format_time(self.gladius_session.time_elapsed());
+    /// The gauge of `kind`, unless it doesn't apply to the mode
+    fn gauge(&self, kind: GaugeKind, config: &Config) -> Option<Gauge<'_>> {
+        let theme = &config.settings.theme.text;
+        let conditions = &self.mode.conditions;
+        let measurement = self.gladius_session.statistics().measurements.last();
 
-        let stats = self
-            .gladius_session
-            .statistics()
-            .measurements
-            .last()
-            .map(|measure| {
-                format!(
-                    "C: %{:.2} | W: {:.2} | A: {:2} | I: {:.2}",
-                    measure.consistency.actual_percent,
-                    measure.wpm.actual,
-                    measure.accuracy.actual,
-                    measure.ipm.actual
+        match kind {
+            GaugeKind::Wpm => conditions.show_speed.then(|| {
+                let wpm = measurement.map_or(0.0, |measurement| measurement.wpm.actual);
+                let ratio = (wpm / WPM_GAUGE_MAX).clamp(0.0, 1.0);
+
+                Gauge::default()
+                    .label(config.tr_with("session.wpm_gauge", &[("wpm", &format!("{wpm:.0}"))]))
+                    .ratio(ratio)
+                    .gauge_style(theme.highlight)
+            }),
+            GaugeKind::Accuracy => {
+                let accuracy = measurement.map_or(100.0, |measurement| measurement.accuracy.actual);
+                let percent = accuracy.round().clamp(0.0, 100.0) as u16;
+
+                let fg = match percent {
+                    95.. => theme.success,
+                    85..95 => theme.warning,
+                    _ => theme.error,
+                };
+
+                Some(
+                    Gauge::default()
+                        .label(config.tr_with(
+                            "session.accuracy_gauge",
+                            &[("accuracy", &format!("{accuracy:.1}"))],
+                        ))
+                        .percent(percent)
+                        .gauge_style(fg),
                 )
-            })
-            .unwrap_or_default();
+            }
+            GaugeKind::Time => conditions
+                .time
+                .as_ref()
+                .filter(|_| conditions.show_speed)
+                .map(|max| {
+                    let max = max.as_secs_f64();
+                    let elapsed = self.gladius_session.time_elapsed();
+
+                    let ratio = (elapsed / max).clamp(0.0, 1.0);
+                    let percent = (ratio * 100.0).round() as u16;
+
+                    let fg = match percent {
+                        60..=80 => theme.warning,
+                        81..=100 => theme.error,
+                        _ => theme.success,
+                    };
 
-        Some(Line::raw(format!("{time} {stats}")))
+                    Gauge::default()
+                        .label(config.tr_with(
+                            "session.time_gauge",
+                            &[
+                                ("elapsed", &format_time(elapsed)),
+                                ("max", &format_time(max)),
+                            ],
+                        ))
+                        .percent(percent)
+                        .gauge_style(fg)
+                }),
+            GaugeKind::Words => conditions.words_typed.map(|goal| {
+                let words_typed = self.gladius_session.words_typed_count();
+                let percent = words_progress(self.words_progress, words_typed, goal);
+
+                Gauge::default()
+                    .label(config.tr_with(
+                        "session.words_gauge",
+                        &[("typed", &words_typed.min(goal)), ("goal", &goal)],
+                    ))
+                    .percent(percent)
+                    .gauge_style(theme.highlight)
+            }),
+        }
+    }
+
+    pub fn render_top(&self, config: &Config) -> Option<Line<'_>> {
+        if self.minimal {
+            return None;
+        }
+
+        let statistics = self.gladius_session.statistics();
+
+        let mut spans = Vec::new();
+        if self.mode.conditions.show_speed {
+            spans.push(Span::raw(format!(
+                "{} ",
+                format_time(self.gladius_session.time_elapsed())
+            )));
+            if let Some(measure) = statistics.measurements.last() {
+                // Consistency only means something once there are a few measurements to compare
+                let consistency = if statistics.measurements.len() < MIN_CONSISTENCY_MEASUREMENTS {
+                    Span::raw("—")
+                } else {
+                    let percent = measure.consistency.actual_percent;
+                    Span::raw(format!("%{percent:.2}"))
+                        .fg(consistency_color(percent, &config.settings.theme.text))
+                };
+                spans.extend([
+                    Span::raw("C: "),
+                    consistency,
+                    Span::raw(format!(
+                        " | W: {:.2} | A: {:2} | I: {:.2}",
+                        measure.wpm.actual, measure.accuracy.actual, measure.ipm.actual
+                    )),
+                ]);
+            }
+        } else {
+            let accuracy = statistics
+                .measurements
+                .last()
+                .map(|measure| format!("A: {:2} | ", measure.accuracy.actual))
+                .unwrap_or_default();
+            spans.push(Span::raw(format!(
+                "{accuracy}E: {}",
+                statistics.counters.errors
+            )));
+        }
+
+        if self.is_blocked() {
+            spans.push(Span::raw(format!(
+                " | {}",
+                config.tr("session.fix_mistake")
+            )));
+        }
+
+        Some(Line::from(spans))
     }
 
     pub fn poll(&mut self, config: &Config) -> Option<Message> {
-        if self.should_end() {
+        if let Some(end_reason) = self.end_reason(config) {
+            let idle = end_reason == EndReason::Idle;
             let statistics = self.gladius_session.clone().finalize();
+            let mut save_note = None;
+            let mut baseline = None;
+            let mut personal_best = false;
+            let mut saved_session = None;
 
             // Save statistics if enabled
-            if let Some(stats_manager) = &config.statistics_manager
-                && let Err(error) = stats_manager.save_session(
-                    &self.mode,
-                    self.mode.mode_name.clone(),
-                    self.mode.source_name.clone(),
-                    &statistics,
-                )
-            {
-                return Some(Message::Error(Box::new(error)));
+            if let Some(stats_manager) = &config.statistics_manager {
+                // Compare against the previous runs before they are joined by this one
+                let previous = stats_manager
+                    .load_sessions_for_config(&self.mode.mode_name, &self.mode.source_name)
+                    .unwrap_or_default();
+                let current = SerializableStatistics::from(&statistics);
+                baseline = previous
+                    .first()
+                    .map(|previous| current.diff(&previous.statistics));
+                let beats_previous = !previous.is_empty()
+                    && previous
+                        .iter()
+                        .all(|session| current.wpm_actual > session.statistics.wpm_actual);
+
+                if let Some(reason) = skip_save_reason(&statistics, config) {
+                    save_note = Some(
+                        config.tr_with("session.not_saved", &[("reason", &config.tr(reason))]),
+                    );
+                } else {
+                    match stats_manager.save_session(
+                        &self.mode,
+                        self.mode.mode_name.clone(),
+                        self.mode.source_name.clone(),
+                        &statistics,
+                        end_reason,
+                    ) {
+                        Ok(session_id) => {
+                            // A run that isn't kept, or whose speed is hidden, is no record
+                            personal_best = beats_previous && self.mode.conditions.show_speed;
+                            saved_session = Some(session_id);
+                        }
+                        Err(error) => return Some(Message::Error(Box::new(error))),
+                    }
+                }
+            }
+
+            if idle && save_note.is_none() {
+                save_note = Some(config.tr("session.ended_idle").to_string());
             }
 
-            return Some(Message::Show(page::Stats::from(statistics).into()));
+            let session_config = SessionConfig::from_mode(
+                &self.mode,
+                self.mode.mode_name.clone(),
+                self.mode.source_name.clone(),
+            );
+
+            let mut stats = page::Stats::new(statistics, session_config)
+                .with_review(self.gladius_session.clone());
+            if !self.mode.conditions.show_speed {
+                stats = stats.without_speed();
+            }
+            if let Some(note) = save_note {
+                stats = stats.with_status(note);
+            }
+            if let Some(diff) = baseline {
+                stats = stats.with_baseline(diff);
+            }
+            if personal_best {
+                stats = stats.with_personal_best();
+            }
+            if let Some(session_id) = saved_session {
+                stats = stats.with_saved_session(session_id);
+            }
+
+            return Some(Message::Show(stats.into()));
+        }
+
+        // Appending text can make the last word count as untyped again, so the gauge remembers
+        // its progress from before the fetch
+        if let Some(goal) = self.mode.conditions.words_typed {
+            let words_typed = self.gladius_session.words_typed_count();
+            self.words_progress = words_progress(self.words_progress, words_typed, goal);
         }
 
         if let Err(error) = self.fetch_new_text() {
@@ -284,14 +683,87 @@ impl Session {
         None
     }
 
-    pub fn handle_events(&mut self, event: &Event, _config: &Config) -> Option<Message> {
+    /// Ends the session without saving or showing it, returning its statistics
+    pub fn into_export(self) -> SessionExport {
+        let session_config = SessionConfig::from_mode(
+            &self.mode,
+            self.mode.mode_name.clone(),
+            self.mode.source_name.clone(),
+        );
+        SessionExport::new(session_config, &self.gladius_session.finalize())
+    }
+
+    /// Types `character`, recording it if it was a mistake. Returns early if the character is
+    /// rejected
+    fn type_character(&mut self, mut character: char, config: &Config) {
+        let expected = self
+            .gladius_session
+            .get_character(self.gladius_session.input_len())
+            .map(|expected| expected.char);
+        if self.mode.conditions.ignore_case
+            && let Some(expected) = expected
+        {
+            character = fold_case(character, expected);
+        }
+        // The first character of the next word doesn't count as the end of the current one
+        if self.mode.conditions.require_space
+            && expected.is_some_and(char::is_whitespace)
+            && !character.is_whitespace()
+        {
+            if config.settings.bell_on_error {
+                self.ring_bell();
+            }
+            return;
+        }
+        let position = self.gladius_session.input_len();
+        let result = self.gladius_session.input(Some(character));
+        match result {
+            Some((_, CharacterResult::Wrong)) => {
+                self.revealed.insert(position);
+                if let Some(limit) = config.settings.recent_errors
+                    && let Some(expected) = expected
+                {
+                    let mistake = Mistake {
+                        expected,
+                        typed: character,
+                    };
+                    record_mistake(&mut self.recent_mistakes, limit, mistake);
+                }
+            }
+            Some((_, CharacterResult::Correct | CharacterResult::Corrected)) => {
+                self.revealed.remove(&position);
+            }
+            _ => (),
+        }
+        if config.settings.bell_on_error && matches!(result, Some((_, CharacterResult::Wrong))) {
+            self.ring_bell();
+        }
+    }
+
+    pub fn handle_events(&mut self, event: &Event, config: &Config) -> Option<Message> {
         if let Event::Key(key) = event
             && key.is_press()
         {
+            if config.settings.keybindings.is(Action::ToggleMinimal, key) {
+                self.minimal = !self.minimal;
+                return None;
+            }
+            // Commands like quitting or reloading are up to the app, and shouldn't type anything
+            if page::has_command_modifier(key)
+                || config.settings.keybindings.is(Action::ReloadTheme, key)
+            {
+                return None;
+            }
+
+            // Only typing starts the idle timer, as nothing has been timed before that
+            if matches!(key.code, KeyCode::Char(_) | KeyCode::Enter) || self.last_input.is_some() {
+                self.last_input = Some(Instant::now());
+            }
             match key.code {
-                KeyCode::Char(character) => {
-                    self.gladius_session.input(Some(character));
-                }
+                KeyCode::Char(_) | KeyCode::Enter if self.is_blocked() => (),
+                KeyCode::Char(character) => self.type_character(character, config),
+                // Line breaks of multi-line texts (e.g. code) are typed with enter
+                KeyCode::Enter => self.type_character('\n', config),
                 KeyCode::Backspace if self.mode.conditions.allow_deletions => {
                     self.gladius_session.input(None);
                 }
@@ -303,6 +775,26 @@ impl Session {
     }
 }
 
+/// Checks whether a line at the given offset from the active line should be shown.
+///
+/// By default the active line is centered between `show_ghost_lines` lines on each side. With
+/// `typed_context_lines` set, only that many already-typed lines are kept above the active line,
+/// and the rest of the window is spent on upcoming lines. Since `render_lines` only reports lines
+/// that exist, the active line moves down from the top until enough typed lines are above it.
+fn is_line_visible(offset: isize, config: &Config) -> bool {
+    let ghost_lines = config.settings.show_ghost_lines;
+
+    let Some(context) = config.settings.typed_context_lines else {
+        return offset.unsigned_abs() <= ghost_lines;
+    };
+
+    let window = ghost_lines * 2;
+    let above = context.min(window) as isize;
+    let below = window as isize - above;
+
+    (-above..=below).contains(&offset)
+}
+
 fn create_line_text_colors(relative_idx: usize, config: &Config) -> (Color, Color, Color, Color) {
     let theme = &config.settings.theme;
     if config.settings.disable_ghost_fade || relative_idx == 0 {
@@ -323,6 +815,60 @@ fn create_line_text_colors(relative_idx: usize, config: &Config) -> (Color, Colo
     }
 }
 
+/// Returns why a finished session shouldn't be saved to the history, if it shouldn't, as the key
+/// of a UI string
+fn skip_save_reason(statistics: &Statistics, config: &Config) -> Option<&'static str> {
+    let settings = &config.settings.statistic;
+    if is_too_short(
+        statistics.counters.adds,
+        statistics.duration.as_secs_f64(),
+        settings,
+    ) {
+        return Some("session.reason_too_short");
+    }
+
+    if statistics.accuracy.actual < settings.min_accuracy_to_save {
+        return Some("session.reason_accuracy");
+    }
+
+    None
+}
+
+/// Progress towards the `goal` of typed words in percent, never below the previously `shown`
+/// progress. Only reaches 100% once the goal is met
+fn words_progress(shown: u16, words_typed: usize, goal: usize) -> u16 {
+    let percent = words_typed
+        .saturating_mul(100)
+        .checked_div(goal)
+        .map_or(100, |percent| percent.min(100) as u16);
+    shown.max(percent)
+}
+
+/// Whether a session with `characters` typed over `seconds` is too short to be worth saving
+fn is_too_short(characters: usize, seconds: f64, settings: &StatisticsConfig) -> bool {
+    characters < settings.min_characters_to_save || seconds < settings.min_duration_to_save
+}
+
+/// Returns `expected` if `typed` only differs from it by case, otherwise `typed`.
+///
+/// Characters that lowercase into multiple characters (e.g. 'İ') are compared strictly.
+fn fold_case(typed: char, expected: char) -> char {
+    let mut typed_lower = typed.to_lowercase();
+    let mut expected_lower = expected.to_lowercase();
+
+    match (
+        typed_lower.next(),
+        typed_lower.next(),
+        expected_lower.next(),
+        expected_lower.next(),
+    ) {
+        (Some(typed_lower), None, Some(expected_lower), None) if typed_lower == expected_lower => {
+            expected
+        }
+        _ => typed,
+    }
+}
+
 #[derive(Display)]
 #[display("{minutes}:{seconds}")]
 struct Time {
@@ -330,6 +876,94 @@ struct Time {
     minutes: u16,
 }
 
+/// Adds `mistake` to the front of `mistakes`, keeping at most `limit` of them
+fn record_mistake(mistakes: &mut VecDeque<Mistake>, limit: usize, mistake: Mistake) {
+    mistakes.push_front(mistake);
+    mistakes.truncate(limit);
+}
+
+/// Shows whitespace as a visible symbol, so mistyped spaces stand out in the mistakes panel
+fn visible_char(character: char) -> String {
+    match character {
+        ' ' => "␣".to_string(),
+        '\n' => "⏎".to_string(),
+        '\t' => "⇥".to_string(),
+        character => character.to_string(),
+    }
+}
+
+/// Whether the character at `index` is hidden, with the cursor at `cursor`
+fn is_hidden(
+    conditions: &Conditions,
+    index: usize,
+    cursor: usize,
+    revealed: &HashSet<usize>,
+) -> bool {
+    if index > cursor {
+        return conditions.blind_ahead;
+    }
+    index == cursor && conditions.reveal_on_error && !revealed.contains(&index)
+}
+
+/// The color of a consistency, in percent
+const fn consistency_color(percent: f64, theme: &TextTheme) -> Color {
+    if percent >= GOOD_CONSISTENCY {
+        theme.success
+    } else if percent >= OKAY_CONSISTENCY {
+        theme.warning
+    } else {
+        theme.error
+    }
+}
+
+/// Whether the result of the typed character at `index` is still withheld, with the cursor at
+/// `cursor`
+const fn is_feedback_delayed(conditions: &Conditions, index: usize, cursor: usize) -> bool {
+    index < cursor && index + conditions.feedback_delay >= cursor
+}
+
+/// Whether the result of any typed character of `word` is still withheld, with the cursor at
+/// `cursor`
+fn is_word_feedback_delayed(conditions: &Conditions, word: &Word, cursor: usize) -> bool {
+    // The withheld characters are the last ones typed, so the last typed one of the word tells
+    word.end
+        .min(cursor)
+        .checked_sub(1)
+        .is_some_and(|last| word.start <= last && is_feedback_delayed(conditions, last, cursor))
+}
+
+/// Cells taken by the widest character of `text`, at least one
+fn widest_char(text: &str) -> usize {
+    text.chars()
+        .map(|character| Span::raw(character.to_string()).width())
+        .max()
+        .unwrap_or(1)
+        .max(1)
+}
+
+/// The number of characters gladius may put on a line of `width` cells. It wraps by characters,
+/// so lines have to fit even if all of them are as wide as the `widest_char`. Text mixing wide
+/// and narrow characters gets shorter lines than would fit
+fn wrap_length(width: u16, widest_char: usize) -> usize {
+    (usize::from(width) / widest_char).max(1)
+}
+
+/// The text shown for a character. Hidden characters are masked with one [`MASK`] per terminal
+/// cell, so the layout doesn't shift when they are revealed. Line breaks are shown as the
+/// `newline_glyph`, if set
+fn display_char(character: char, hidden: bool, newline_glyph: Option<char>) -> String {
+    if character == '\n'
+        && let Some(glyph) = newline_glyph
+    {
+        return glyph.to_string();
+    }
+    let text = character.to_string();
+    if !hidden || character.is_whitespace() {
+        return text;
+    }
+    MASK.to_string().repeat(Span::raw(text).width())
+}
+
 fn format_time(time: f64) -> Time {
     Time {
         seconds: time.rem(60.0).trunc() as u16,
@@ -373,3 +1007,334 @@ fn gauge_constraints(area: Rect, desired_count: usize) -> Vec<Constraint> {
 
     heights.into_iter().map(Constraint::Length).collect()
 }
+
+#[cfg(test)]
+mod test {
+    use std::collections::{HashSet, VecDeque};
+
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+    use gladius::{TypingSession, render::LineRenderConfig};
+    use ratatui::{Terminal, backend::TestBackend, text::Span};
+
+    use crate::statistics::StatisticsManager;
+
+    use super::{
+        Conditions, Config, EndReason, Mistake, Mode, Session, State, StatisticsConfig, TextTheme,
+        Word, consistency_color, display_char, fold_case, is_feedback_delayed, is_hidden,
+        is_too_short, is_word_feedback_delayed, record_mistake, widest_char, words_progress,
+        wrap_length,
+    };
+
+    #[test]
+    fn fold_case_accepts_case_mismatches() {
+        let expected = "Hello World";
+        let typed = "hELLO wORLD";
+
+        let folded: String = typed
+            .chars()
+            .zip(expected.chars())
+            .map(|(typed, expected)| fold_case(typed, expected))
+            .collect();
+
+        assert_eq!(folded, expected);
+    }
+
+    #[test]
+    fn fold_case_keeps_wrong_characters() {
+        assert_eq!(fold_case('x', 'H'), 'x');
+        assert_eq!(fold_case('h', 'j'), 'h');
+    }
+
+    #[test]
+    fn fold_case_is_strict_for_multi_char_lowercase() {
+        // 'İ' lowercases to "i̇" (two chars), so it must be typed exactly
+        assert_eq!(fold_case('i', 'İ'), 'i');
+        assert_eq!(fold_case('İ', 'İ'), 'İ');
+    }
+
+    #[test]
+    fn hidden_characters_keep_their_width() {
+        assert_eq!(display_char('a', false, None), "a");
+        assert_eq!(display_char('a', true, None), "·");
+        assert_eq!(display_char('漢', true, None), "··");
+        assert_eq!(display_char(' ', true, None), " ");
+        assert_eq!(display_char('\n', true, Some('↵')), "↵");
+    }
+
+    #[test]
+    fn mistakes_reveal_the_character_under_the_cursor() {
+        let conditions = Conditions {
+            blind_ahead: true,
+            reveal_on_error: true,
+            ..Conditions::neutral()
+        };
+        let mut revealed = HashSet::new();
+
+        assert!(!is_hidden(&conditions, 2, 3, &revealed));
+        assert!(is_hidden(&conditions, 3, 3, &revealed));
+        assert!(is_hidden(&conditions, 4, 3, &revealed));
+
+        revealed.insert(3);
+        assert!(!is_hidden(&conditions, 3, 3, &revealed));
+        assert!(is_hidden(&conditions, 4, 3, &revealed));
+    }
+
+    #[test]
+    fn feedback_is_shown_once_the_cursor_is_far_enough_ahead() {
+        let conditions = Conditions {
+            feedback_delay: 2,
+            ..Conditions::neutral()
+        };
+
+        assert!(!is_feedback_delayed(&conditions, 0, 3));
+        assert!(is_feedback_delayed(&conditions, 1, 3));
+        assert!(is_feedback_delayed(&conditions, 2, 3));
+        // Untyped characters have nothing to show
+        assert!(!is_feedback_delayed(&conditions, 3, 3));
+
+        assert!(!is_feedback_delayed(&Conditions::neutral(), 2, 3));
+    }
+
+    #[test]
+    fn words_are_not_underlined_while_a_mistake_is_withheld() {
+        let conditions = Conditions {
+            feedback_delay: 2,
+            ..Conditions::neutral()
+        };
+        // "abc", with a mistake on the 'c'
+        let word = Word {
+            start: 0,
+            end: 3,
+            state: State::Wrong,
+        };
+
+        assert!(is_word_feedback_delayed(&conditions, &word, 3));
+        // The first characters are no longer delayed, but the mistake is
+        assert!(is_word_feedback_delayed(&conditions, &word, 4));
+        assert!(!is_word_feedback_delayed(&conditions, &word, 5));
+        assert!(!is_word_feedback_delayed(&conditions, &word, 0));
+        assert!(!is_word_feedback_delayed(&Conditions::neutral(), &word, 3));
+    }
+
+    #[test]
+    fn trivial_sessions_are_too_short_to_save() {
+        let settings = StatisticsConfig::default();
+
+        assert!(is_too_short(3, 5.0, &settings));
+        assert!(!is_too_short(10, 5.0, &settings));
+
+        let settings = StatisticsConfig {
+            min_duration_to_save: 10.0,
+            ..StatisticsConfig::default()
+        };
+        assert!(is_too_short(50, 5.0, &settings));
+    }
+
+    #[test]
+    fn words_progress_never_decreases() {
+        let goal = 6;
+        // Appending text while the last word is typed counts it as untyped again
+        let words_typed = [0, 1, 2, 3, 2, 3, 4, 5, 4, 5, 6, 7];
+
+        let mut shown = 0;
+        for typed in words_typed {
+            let progress = words_progress(shown, typed, goal);
+            assert!(progress >= shown);
+            assert!(progress <= 100);
+            assert_eq!(progress == 100, typed >= goal);
+            shown = progress;
+        }
+    }
+
+    #[test]
+    fn recent_mistakes_are_capped_newest_first() {
+        let mut mistakes = VecDeque::new();
+        for typed in ['a', 'b', 'c', 'd'] {
+            let mistake = Mistake {
+                expected: 'x',
+                typed,
+            };
+            record_mistake(&mut mistakes, 3, mistake);
+        }
+
+        let typed = mistakes
+            .iter()
+            .map(|mistake| mistake.typed)
+            .collect::<String>();
+        assert_eq!(typed, "dcb");
+    }
+
+    #[test]
+    fn consistency_is_colored_by_thresholds() {
+        let theme = TextTheme::default();
+
+        assert_eq!(consistency_color(95.0, &theme), theme.success);
+        assert_eq!(consistency_color(80.0, &theme), theme.success);
+        assert_eq!(consistency_color(70.0, &theme), theme.warning);
+        assert_eq!(consistency_color(12.5, &theme), theme.error);
+    }
+
+    #[test]
+    fn line_breaks_are_typed_with_enter() {
+        let config = Config::default();
+        let mut session = Session::new(&config, Mode::from_text("ab\ncd".to_string())).unwrap();
+
+        for code in [
+            KeyCode::Char('a'),
+            KeyCode::Char('b'),
+            KeyCode::Enter,
+            KeyCode::Char('c'),
+            KeyCode::Char('d'),
+        ] {
+            let key = Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+            session.handle_events(&key, &config);
+        }
+
+        assert!(session.gladius_session.is_fully_typed());
+        assert_eq!(session.gladius_session.statistics().counters.errors, 0);
+    }
+
+    #[test]
+    fn commands_are_not_typed() {
+        let config = Config::default();
+        let mut session = Session::new(&config, Mode::from_text("quit".to_string())).unwrap();
+
+        let quit = Event::Key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL));
+        session.handle_events(&quit, &config);
+
+        assert_eq!(session.gladius_session.input_len(), 0);
+        assert!(!session.is_busy());
+    }
+
+    fn type_text(session: &mut Session, config: &Config, text: &str) {
+        for character in text.chars() {
+            let key = Event::Key(KeyEvent::new(KeyCode::Char(character), KeyModifiers::NONE));
+            session.handle_events(&key, config);
+        }
+    }
+
+    #[test]
+    fn missing_space_is_typed_into_it_by_default() {
+        let config = Config::default();
+        let mut session = Session::new(&config, Mode::from_text("ab cd".to_string())).unwrap();
+
+        type_text(&mut session, &config, "abcd");
+
+        assert_eq!(session.gladius_session.input_len(), 4);
+        assert!(session.gladius_session.statistics().counters.errors > 0);
+    }
+
+    #[test]
+    fn required_space_rejects_the_next_word() {
+        let config = Config::default();
+        let mut session = Session::new(&config, Mode::from_text("ab cd".to_string())).unwrap();
+        session.mode.conditions.require_space = true;
+
+        type_text(&mut session, &config, "abcd");
+        assert_eq!(session.gladius_session.input_len(), 2);
+
+        type_text(&mut session, &config, " cd");
+        assert!(session.gladius_session.is_fully_typed());
+        assert_eq!(session.gladius_session.statistics().counters.errors, 0);
+    }
+
+    #[test]
+    fn idle_sessions_are_saved() {
+        let directory = std::env::temp_dir().join(format!("octotype-idle-{}", std::process::id()));
+        let manager = StatisticsManager::new(directory.clone()).unwrap();
+        let config = Config::default().with(|config| {
+            config.settings.idle_timeout_seconds = Some(0);
+            config.settings.statistic.min_characters_to_save = 0;
+            config.settings.statistic.min_duration_to_save = 0.0;
+            config.settings.statistic.min_accuracy_to_save = 0.0;
+            config.statistics_manager = Some(manager.clone());
+        });
+
+        let mut session = Session::new(&config, Mode::from_text("idle".to_string())).unwrap();
+        let key = Event::Key(KeyEvent::new(KeyCode::Char('i'), KeyModifiers::NONE));
+        session.handle_events(&key, &config);
+        assert!(session.poll(&config).is_some());
+
+        let saved = manager.load_all_sessions().unwrap();
+        std::fs::remove_dir_all(directory).unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].end_reason, EndReason::Idle);
+        assert!(!saved[0].completed);
+    }
+
+    #[test]
+    fn low_accuracy_sessions_are_not_saved() {
+        let directory =
+            std::env::temp_dir().join(format!("octotype-accuracy-{}", std::process::id()));
+        let manager = StatisticsManager::new(directory.clone()).unwrap();
+        let config = Config::default().with(|config| {
+            config.settings.statistic.min_characters_to_save = 0;
+            config.settings.statistic.min_duration_to_save = 0.0;
+            config.settings.statistic.min_accuracy_to_save = 90.0;
+            config.statistics_manager = Some(manager.clone());
+        });
+
+        let mut session = Session::new(&config, Mode::from_text("abcd".to_string())).unwrap();
+        type_text(&mut session, &config, "abxy");
+        assert!(session.poll(&config).is_some());
+
+        let saved = manager.load_all_sessions().unwrap();
+        let _ = std::fs::remove_dir_all(directory);
+        assert!(saved.is_empty());
+    }
+
+    #[test]
+    fn fixes_are_only_required_below_the_accuracy() {
+        let config = Config::default();
+        let mut session = Session::new(&config, Mode::from_text("abcdefgh".to_string())).unwrap();
+        session.mode.conditions.require_fix_before_continue = true;
+        session.mode.conditions.fix_below_accuracy = 50;
+
+        // 4 of 5 characters are right, so the mistake may stand
+        type_text(&mut session, &config, "abcdx");
+        type_text(&mut session, &config, "f");
+        assert_eq!(session.gladius_session.input_len(), 6);
+
+        session.mode.conditions.fix_below_accuracy = 95;
+        type_text(&mut session, &config, "x");
+        type_text(&mut session, &config, "h");
+        assert_eq!(session.gladius_session.input_len(), 7);
+    }
+
+    #[test]
+    fn wide_lines_fit_their_width_in_cells() {
+        let text = "漢字 漢字 漢字 漢字 ab cd";
+        let session = TypingSession::new(text).unwrap();
+        let widths = session.render_lines(
+            |line| {
+                Some(
+                    line.contents
+                        .iter()
+                        .map(|ctx| Span::raw(ctx.character.char.to_string()).width())
+                        .sum::<usize>(),
+                )
+            },
+            LineRenderConfig::new(wrap_length(10, widest_char(text))),
+        );
+
+        assert!(widths.len() > 1);
+        assert!(widths.iter().all(|width| *width <= 10), "{widths:?}");
+        assert_eq!(wrap_length(10, widest_char("abc")), 10);
+    }
+
+    #[test]
+    fn caret_is_placed_by_display_width() {
+        let config = Config::default().with(|config| config.settings.session.minimal = true);
+        let mut session = Session::new(&config, Mode::from_text("漢字 ab".to_string())).unwrap();
+        let key = Event::Key(KeyEvent::new(KeyCode::Char('漢'), KeyModifiers::NONE));
+        session.handle_events(&key, &config);
+
+        let mut terminal = Terminal::new(TestBackend::new(20, 5)).unwrap();
+        terminal
+            .draw(|frame| session.render(frame, frame.area(), &config))
+            .unwrap();
+
+        let cursor = terminal.get_cursor_position().unwrap();
+        assert_eq!(terminal.backend().buffer()[cursor].symbol(), "字");
+    }
+}
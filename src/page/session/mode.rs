@@ -4,18 +4,29 @@ use std::{
     path::PathBuf,
     process::{Child, Command, Stdio},
     string::FromUtf8Error,
+    sync::atomic::{AtomicBool, Ordering},
     time::Duration,
 };
 
 use derive_more::From;
-use rand::{rng, seq::SliceRandom};
+use rand::{
+    Rng, SeedableRng,
+    distr::{Distribution, weighted::WeightedIndex},
+    rng,
+    rngs::StdRng,
+    seq::SliceRandom,
+};
 use thiserror::Error;
 
-use crate::config::{
-    Config, ModeConfig, SourceConfig,
-    mode::{ConditionConfig, ParseConditionError},
-    parameters::ParameterValues,
-    source::{Formatting, GeneratorDefinition, ListSource},
+use crate::{
+    cache::SourceCache,
+    config::{
+        Config, ModeConfig, SourceConfig,
+        mode::{ConditionCombinator, ConditionConfig, ParseConditionError},
+        multi_source::SourceOrder,
+        parameters::ParameterValues,
+        source::{COMMON_WORDS, Formatting, GeneratorDefinition, ListSource, Transform},
+    },
 };
 
 #[derive(Debug, Error, From)]
@@ -42,16 +53,22 @@ pub struct Mode {
 }
 
 impl Mode {
+    /// Creates a mode typing the text of `sources`. Multiple sources are combined into one text
     pub fn from_config(
         config: &Config,
         mode: ModeConfig,
-        source: SourceConfig,
+        sources: Vec<SourceConfig>,
         parameters: ParameterValues,
     ) -> Result<Self, CreateModeError> {
         let mode_name = mode.meta.name.clone();
-        let source_name = source.meta.name.clone();
+        let source_name = combined_name(&sources);
+        let seed = session_seed(config, &mode);
+        let parameters = parameters.with_seed(seed);
         let resolved_conditions = Conditions::from_config(mode.conditions, &parameters)?;
-        let resolved_source = Source::from_config(config, source, &parameters)?;
+        let mut resolved_source = Source::from_configs(config, sources, &parameters, seed)?;
+        resolved_source
+            .transforms
+            .extend(resolve_transforms(mode.transforms, &parameters)?);
         Ok(Self {
             conditions: resolved_conditions,
             source: resolved_source,
@@ -59,6 +76,16 @@ impl Mode {
             source_name,
         })
     }
+
+    /// Creates a mode typing the given text once, without any goals or restrictions
+    pub fn from_text(text: String) -> Self {
+        Self {
+            conditions: Conditions::neutral(),
+            source: Source::from_text(text),
+            mode_name: "Text".to_string(),
+            source_name: "Custom text".to_string(),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -67,6 +94,21 @@ pub struct Conditions {
     pub words_typed: Option<usize>,
     pub allow_deletions: bool,
     pub allow_errors: bool,
+    pub ignore_case: bool,
+    pub require_fix_before_continue: bool,
+    /// The running accuracy (in percent) below which `require_fix_before_continue` blocks input
+    pub fix_below_accuracy: usize,
+    /// Reject other characters where a space is expected, so a word has to be ended explicitly
+    pub require_space: bool,
+    /// Hide the text ahead of the cursor
+    pub blind_ahead: bool,
+    /// Hide the character under the cursor until a mistake is made on it
+    pub reveal_on_error: bool,
+    /// Show Wpm and time. Full statistics are saved either way
+    pub show_speed: bool,
+    /// How many characters the cursor has to move past a character before its result is shown
+    pub feedback_delay: usize,
+    pub combinator: ConditionCombinator,
 }
 
 impl Conditions {
@@ -79,6 +121,15 @@ impl Conditions {
             words_typed,
             allow_deletions,
             allow_errors,
+            ignore_case,
+            require_fix_before_continue,
+            fix_below_accuracy,
+            require_space,
+            blind_ahead,
+            reveal_on_error,
+            show_speed,
+            feedback_delay,
+            combinator,
         } = condition_config;
 
         let time = time
@@ -97,26 +148,126 @@ impl Conditions {
 
         let allow_errors = allow_errors.parse_bool("allow_errors", parameters)?;
 
+        let ignore_case = ignore_case.parse_bool("ignore_case", parameters)?;
+
+        let require_fix_before_continue =
+            require_fix_before_continue.parse_bool("require_fix_before_continue", parameters)?;
+
+        let fix_below_accuracy =
+            fix_below_accuracy.parse_number("fix_below_accuracy", parameters)?;
+
+        let require_space = require_space.parse_bool("require_space", parameters)?;
+
+        let blind_ahead = blind_ahead.parse_bool("blind_ahead", parameters)?;
+
+        let reveal_on_error = reveal_on_error.parse_bool("reveal_on_error", parameters)?;
+
+        let show_speed = show_speed.parse_bool("show_speed", parameters)?;
+
+        let feedback_delay = feedback_delay.parse_number("feedback_delay", parameters)?;
+
         Ok(Self {
             time,
             words_typed,
             allow_deletions,
             allow_errors,
+            ignore_case,
+            require_fix_before_continue,
+            fix_below_accuracy,
+            require_space,
+            blind_ahead,
+            reveal_on_error,
+            show_speed,
+            feedback_delay,
+            combinator,
         })
     }
+
+    /// Conditions without goals, allowing deletions and errors
+    pub const fn neutral() -> Self {
+        Self {
+            time: None,
+            words_typed: None,
+            allow_deletions: true,
+            allow_errors: true,
+            ignore_case: false,
+            require_fix_before_continue: false,
+            fix_below_accuracy: 100,
+            require_space: false,
+            blind_ahead: false,
+            reveal_on_error: false,
+            show_speed: true,
+            feedback_delay: 0,
+            combinator: ConditionCombinator::Any,
+        }
+    }
+
+    /// Checks whether the goals (time and words typed) have been reached, combined by the
+    /// `combinator`. Returns `false` if no goals are set.
+    pub fn goals_met(&self, time_elapsed: f64, words_typed: usize) -> bool {
+        let mut goals = [
+            self.time.map(|max| time_elapsed > max.as_secs_f64()),
+            self.words_typed.map(|target| words_typed >= target),
+        ]
+        .into_iter()
+        .flatten()
+        .peekable();
+
+        if goals.peek().is_none() {
+            return false;
+        }
+
+        match self.combinator {
+            ConditionCombinator::Any => goals.any(|met| met),
+            ConditionCombinator::All => goals.all(|met| met),
+        }
+    }
 }
 
 #[derive(Debug)]
-pub enum Source {
+pub struct Source {
+    generator: Generator,
+    transforms: Vec<Transform>,
+    /// Used for shuffling and transforms, so a seed always produces the same text
+    rng: StdRng,
+}
+
+#[derive(Debug)]
+enum Generator {
+    /// Multiple sources, joined into one text
+    Combined {
+        sources: Vec<Source>,
+        /// The output of each source in the current fetch
+        outputs: Vec<Output>,
+        order: SourceOrder,
+        skip_failed: bool,
+    },
     Command {
         command: Command,
         child: Option<Box<Child>>,
         format: Formatting,
+        /// Set if caching is enabled
+        cache: Option<SourceCache>,
     },
     List {
         words: Vec<String>,
         randomize: bool,
     },
+    CommonWords {
+        /// The most common words, most frequent first
+        words: Vec<&'static str>,
+        /// Picks a word by its frequency
+        weights: WeightedIndex<f64>,
+        count: usize,
+    },
+}
+
+/// The output of one of the combined sources
+#[derive(Debug)]
+enum Output {
+    Fetching,
+    Done(String),
+    Skipped,
 }
 
 #[derive(Debug, Error, From)]
@@ -124,16 +275,28 @@ pub enum FetchError {
     #[error("Fetch I/O Error: {0}")]
     IO(std::io::Error),
 
-    #[error("Failed to get output of command")]
+    #[error("Source output is not valid UTF-8: {0}")]
     Output(FromUtf8Error),
 
     #[error("Encountered error: {0}")]
     SourceError(String),
+
+    #[error("Fetching was cancelled")]
+    Cancelled,
 }
 
 impl Source {
     pub fn fetch(&mut self) -> Result<String, FetchError> {
+        self.fetch_cancellable(&AtomicBool::new(false))
+    }
+
+    /// Like [`Source::fetch`], but gives up once `cancelled` is set. A running source process is
+    /// killed when the source is dropped
+    pub fn fetch_cancellable(&mut self, cancelled: &AtomicBool) -> Result<String, FetchError> {
         loop {
+            if cancelled.load(Ordering::Relaxed) {
+                return Err(FetchError::Cancelled);
+            }
             if let Some(words) = self.try_fetch()? {
                 return Ok(words);
             }
@@ -141,12 +304,163 @@ impl Source {
     }
 
     pub fn try_fetch(&mut self) -> Result<Option<String>, FetchError> {
+        let Some(text) = self.generator.try_fetch(&mut self.rng)? else {
+            return Ok(None);
+        };
+
+        let text = self
+            .transforms
+            .iter()
+            .fold(text, |text, transform| transform.apply(text, &mut self.rng));
+
+        if text.trim().is_empty() {
+            return Err(FetchError::SourceError(
+                "Source output was empty after applying transforms!".to_string(),
+            ));
+        }
+
+        Ok(Some(text))
+    }
+
+    /// Creates a source that always produces the given text
+    pub fn from_text(text: String) -> Self {
+        Self {
+            generator: Generator::List {
+                words: vec![text],
+                randomize: false,
+            },
+            transforms: Vec::new(),
+            rng: StdRng::from_rng(&mut rng()),
+        }
+    }
+
+    pub fn from_config(
+        config: &Config,
+        source_config: SourceConfig,
+        parameters: &ParameterValues,
+        seed: u64,
+    ) -> Result<Self, CreateModeError> {
+        let SourceConfig {
+            meta,
+            generator,
+            transforms,
+            ..
+        } = source_config;
+
+        Ok(Self {
+            generator: Generator::from_config(config, &meta.name, generator, parameters)?,
+            transforms: resolve_transforms(transforms, parameters)?,
+            rng: StdRng::seed_from_u64(seed),
+        })
+    }
+
+    /// Always runs the source, instead of using its cached output
+    pub fn without_cache(mut self) -> Self {
+        if let Generator::Command { cache, .. } = &mut self.generator {
+            *cache = None;
+        }
+        self
+    }
+
+    /// Creates a source from one or more source configs, combining them according to the
+    /// `multi_source` settings
+    pub fn from_configs(
+        config: &Config,
+        source_configs: Vec<SourceConfig>,
+        parameters: &ParameterValues,
+        seed: u64,
+    ) -> Result<Self, CreateModeError> {
+        let settings = &config.settings.multi_source;
+
+        let mut sources = Vec::new();
+        let mut first_error = None;
+        for (index, source_config) in source_configs.into_iter().enumerate() {
+            // Combining a source with itself shouldn't repeat the same text
+            let seed = seed.wrapping_add(index as u64);
+            match Self::from_config(config, source_config, parameters, seed) {
+                Ok(source) => sources.push(source),
+                Err(error) if settings.skip_failed => {
+                    first_error.get_or_insert(error);
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        match (sources.len(), first_error) {
+            (0, Some(error)) => Err(error),
+            (1, _) => Ok(sources.remove(0)),
+            _ => Ok(Self {
+                generator: Generator::Combined {
+                    outputs: sources.iter().map(|_| Output::Fetching).collect(),
+                    sources,
+                    order: settings.order,
+                    skip_failed: settings.skip_failed,
+                },
+                transforms: Vec::new(),
+                rng: StdRng::seed_from_u64(seed),
+            }),
+        }
+    }
+}
+
+impl Generator {
+    fn try_fetch(&mut self, rng: &mut StdRng) -> Result<Option<String>, FetchError> {
         match self {
+            Self::Combined {
+                sources,
+                outputs,
+                order,
+                skip_failed,
+            } => {
+                for (source, output) in sources.iter_mut().zip(outputs.iter_mut()) {
+                    if !matches!(output, Output::Fetching) {
+                        continue;
+                    }
+                    match source.try_fetch() {
+                        Ok(Some(text)) => *output = Output::Done(text),
+                        Ok(None) => (),
+                        Err(_) if *skip_failed => *output = Output::Skipped,
+                        Err(error) => return Err(error),
+                    }
+                }
+
+                if outputs
+                    .iter()
+                    .any(|output| matches!(output, Output::Fetching))
+                {
+                    return Ok(None);
+                }
+
+                // Start over on the next fetch
+                let texts = outputs
+                    .iter_mut()
+                    .filter_map(|output| match std::mem::replace(output, Output::Fetching) {
+                        Output::Done(text) => Some(text),
+                        Output::Fetching | Output::Skipped => None,
+                    })
+                    .collect::<Vec<String>>();
+
+                if texts.is_empty() {
+                    return Err(FetchError::SourceError("Every source failed".to_string()));
+                }
+
+                Ok(Some(order.join(&texts)))
+            }
             Self::Command {
                 command,
                 child,
                 format,
+                cache,
             } => {
+                // The cache only serves the first fetch. Refills of the session run the command, as
+                // they would repeat the same text otherwise
+                if child.is_none()
+                    && let Some(output) = cache.as_ref().and_then(SourceCache::read)
+                {
+                    *cache = None;
+                    return Ok(parse_output(output, format));
+                }
+
                 // Take child process out
                 let Some(mut child_process) = child.take() else {
                     *child = Some(Box::new(command.spawn()?));
@@ -161,8 +475,9 @@ impl Source {
 
                 let process_output = child_process.wait_with_output()?;
 
-                let stderr = String::from_utf8(process_output.stderr)?;
-                let stdout = String::from_utf8(process_output.stdout)?;
+                // Stderr is only shown, so invalid bytes don't matter there
+                let stderr = String::from_utf8_lossy(&process_output.stderr);
+                let stdout = decode_output(process_output.stdout)?;
 
                 if !status.success() {
                     return Err(FetchError::SourceError(format!(
@@ -176,26 +491,38 @@ impl Source {
                     ));
                 }
 
+                if let Some(cache) = cache.take() {
+                    cache.write(&stdout);
+                }
+
                 Ok(parse_output(stdout, format))
             }
             Self::List { words, randomize } => {
                 if *randomize {
-                    let mut rng = rng();
-                    words.shuffle(&mut rng);
+                    words.shuffle(rng);
                     return Ok(Some(words.join(" ")));
                 }
                 Ok(Some(words.join(" ")))
             }
+            Self::CommonWords {
+                words,
+                weights,
+                count,
+            } => Ok(Some(
+                (0..*count)
+                    .map(|_| words[weights.sample(rng)])
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            )),
         }
     }
 
-    pub fn from_config(
+    fn from_config(
         config: &Config,
-        source_config: SourceConfig,
+        name: &str,
+        generator: GeneratorDefinition,
         parameters: &ParameterValues,
     ) -> Result<Self, CreateModeError> {
-        let SourceConfig { generator, .. } = source_config;
-
         match generator {
             GeneratorDefinition::Command {
                 command,
@@ -215,6 +542,16 @@ impl Source {
                     .map(|string| parameters.replace_values(string))
                     .collect::<Vec<String>>();
 
+                let settings = &config.settings.cache;
+                let cache = settings.enabled.then(|| {
+                    SourceCache::new(
+                        config.cache_dir(),
+                        name,
+                        &program,
+                        Duration::from_secs(settings.ttl_seconds),
+                    )
+                });
+
                 let mut command = std::process::Command::new(program.remove(0));
                 command
                     .args(program)
@@ -226,6 +563,7 @@ impl Source {
                     command,
                     format: formatting,
                     child: None,
+                    cache,
                 })
             }
             GeneratorDefinition::List { source, randomize } => {
@@ -252,10 +590,112 @@ impl Source {
                 };
                 Ok(Self::List { words, randomize })
             }
+            GeneratorDefinition::CommonWords {
+                frequency_rank_max,
+                words,
+            } => {
+                let rank_max = frequency_rank_max.parse_number("frequency_rank_max", parameters)?;
+                let count = words.parse_number("words", parameters)?;
+
+                let words = COMMON_WORDS
+                    .split_whitespace()
+                    .take(rank_max.max(1))
+                    .collect::<Vec<_>>();
+                // Word frequencies roughly follow Zipf's law: The word of rank n is about n times
+                // less frequent than the most common word
+                let weights =
+                    WeightedIndex::new((1..=words.len()).map(|rank| (rank as f64).recip()))
+                        .expect("The bundled word list is not empty");
+
+                Ok(Self::CommonWords {
+                    words,
+                    weights,
+                    count,
+                })
+            }
+        }
+    }
+}
+
+impl SourceOrder {
+    fn join(self, texts: &[String]) -> String {
+        match self {
+            Self::Sequential => texts.join(" "),
+            Self::Interleaved => {
+                let mut words = texts
+                    .iter()
+                    .map(|text| text.split_whitespace())
+                    .collect::<Vec<_>>();
+                let mut interleaved = Vec::new();
+                loop {
+                    let round = words
+                        .iter_mut()
+                        .filter_map(Iterator::next)
+                        .collect::<Vec<_>>();
+                    if round.is_empty() {
+                        break;
+                    }
+                    interleaved.extend(round);
+                }
+                interleaved.join(" ")
+            }
+        }
+    }
+}
+
+/// Fills in the replacements of each transform
+fn resolve_transforms(
+    transforms: Vec<Transform>,
+    parameters: &ParameterValues,
+) -> Result<Vec<Transform>, ParseConditionError> {
+    transforms
+        .into_iter()
+        .map(|transform| transform.resolve(parameters))
+        .collect()
+}
+
+/// The seed of a session: The `--seed` flag, the seed of the mode, or a random one
+pub fn session_seed(config: &Config, mode: &ModeConfig) -> u64 {
+    config.seed.or(mode.seed).unwrap_or_else(|| rng().random())
+}
+
+/// Name of the given sources, as shown in the menu and statistics
+pub fn combined_name(sources: &[SourceConfig]) -> String {
+    sources
+        .iter()
+        .map(|source| source.meta.name.as_str())
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+impl Drop for Generator {
+    fn drop(&mut self) {
+        // Kill and reap a source process that is still running, so it doesn't outlive the source
+        if let Self::Command {
+            child: Some(child), ..
+        } = self
+        {
+            let _ = child.kill();
+            let _ = child.wait();
         }
     }
 }
 
+/// Decodes the output of a source. Invalid UTF-8 is rejected, as the replacement characters it
+/// would be decoded to can't be typed
+fn decode_output(bytes: Vec<u8>) -> Result<String, FetchError> {
+    let output = String::from_utf8(bytes)?;
+
+    if output.contains(char::REPLACEMENT_CHARACTER) {
+        return Err(FetchError::SourceError(
+            "Source output contains replacement characters (U+FFFD), which can't be typed"
+                .to_string(),
+        ));
+    }
+
+    Ok(output)
+}
+
 fn parse_output(output: String, format: &Formatting) -> Option<String> {
     let words: String = match format {
         Formatting::Raw => output,
@@ -266,3 +706,260 @@ fn parse_output(output: String, format: &Formatting) -> Option<String> {
     };
     Some(words)
 }
+
+#[cfg(test)]
+mod test {
+    use std::{
+        process::{Command, Stdio},
+        sync::atomic::AtomicBool,
+        time::Duration,
+    };
+
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use crate::{
+        cache::SourceCache,
+        config::{
+            Config,
+            mode::{ConditionCombinator, ConditionValue},
+            multi_source::SourceOrder,
+            parameters::{Definition, ParameterValues},
+            source::{
+                COMMON_WORDS, Formatting, GeneratorDefinition, Transform, create_default_sources,
+            },
+        },
+    };
+
+    use super::{Conditions, FetchError, Generator, Output, Source, decode_output};
+
+    fn combined(sources: Vec<Source>, order: SourceOrder, skip_failed: bool) -> Source {
+        Source {
+            generator: Generator::Combined {
+                outputs: sources.iter().map(|_| Output::Fetching).collect(),
+                sources,
+                order,
+                skip_failed,
+            },
+            transforms: Vec::new(),
+            rng: StdRng::seed_from_u64(0),
+        }
+    }
+
+    fn failing() -> Source {
+        Source {
+            generator: Generator::Command {
+                command: Command::new("false"),
+                child: None,
+                format: Formatting::Raw,
+                cache: None,
+            },
+            transforms: Vec::new(),
+            rng: StdRng::seed_from_u64(0),
+        }
+    }
+
+    fn conditions(combinator: ConditionCombinator) -> Conditions {
+        Conditions {
+            time: Some(Duration::from_secs(60)),
+            words_typed: Some(30),
+            allow_deletions: true,
+            allow_errors: true,
+            ignore_case: false,
+            require_fix_before_continue: false,
+            fix_below_accuracy: 100,
+            require_space: false,
+            blind_ahead: false,
+            reveal_on_error: false,
+            show_speed: true,
+            feedback_delay: 0,
+            combinator,
+        }
+    }
+
+    #[test]
+    fn any_ends_on_first_goal() {
+        let conditions = conditions(ConditionCombinator::Any);
+
+        assert!(!conditions.goals_met(10.0, 10));
+        assert!(conditions.goals_met(61.0, 10));
+        assert!(conditions.goals_met(10.0, 30));
+        assert!(conditions.goals_met(61.0, 30));
+    }
+
+    #[test]
+    fn all_requires_every_goal() {
+        let conditions = conditions(ConditionCombinator::All);
+
+        assert!(!conditions.goals_met(10.0, 10));
+        assert!(!conditions.goals_met(61.0, 10));
+        assert!(!conditions.goals_met(10.0, 30));
+        assert!(conditions.goals_met(61.0, 31));
+    }
+
+    #[test]
+    fn no_goals_never_met() {
+        let mut conditions = conditions(ConditionCombinator::All);
+        conditions.time = None;
+        conditions.words_typed = None;
+
+        assert!(!conditions.goals_met(1000.0, 1000));
+    }
+
+    #[test]
+    fn cancelled_fetch_stops_waiting_for_the_source() {
+        let mut command = Command::new("sleep");
+        command.arg("30");
+        let mut source = Source {
+            generator: Generator::Command {
+                command,
+                child: None,
+                format: Formatting::Raw,
+                cache: None,
+            },
+            transforms: Vec::new(),
+            rng: StdRng::seed_from_u64(0),
+        };
+
+        assert!(source.try_fetch().unwrap().is_none());
+        assert!(matches!(
+            source.fetch_cancellable(&AtomicBool::new(true)),
+            Err(FetchError::Cancelled)
+        ));
+    }
+
+    #[test]
+    fn cache_only_serves_the_first_fetch() {
+        let directory =
+            std::env::temp_dir().join(format!("octotype-refill-{}", std::process::id()));
+        let cache = SourceCache::new(&directory, "Echo", &[], Duration::from_secs(3600));
+        cache.write("cached words");
+        let mut command = Command::new("echo");
+        command.arg("fresh words").stdout(Stdio::piped());
+        let mut source = Source {
+            generator: Generator::Command {
+                command,
+                child: None,
+                format: Formatting::Raw,
+                cache: Some(cache),
+            },
+            transforms: Vec::new(),
+            rng: StdRng::seed_from_u64(0),
+        };
+        let cancelled = AtomicBool::new(false);
+
+        assert_eq!(
+            source.fetch_cancellable(&cancelled).unwrap().trim(),
+            "cached words"
+        );
+        assert_eq!(
+            source.fetch_cancellable(&cancelled).unwrap().trim(),
+            "fresh words"
+        );
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn combined_sources_are_joined_in_order() {
+        let sources = || {
+            vec![
+                Source::from_text("a b c".to_string()),
+                Source::from_text("1 2".to_string()),
+            ]
+        };
+
+        let mut sequential = combined(sources(), SourceOrder::Sequential, false);
+        assert_eq!(sequential.fetch().unwrap(), "a b c 1 2");
+
+        let mut interleaved = combined(sources(), SourceOrder::Interleaved, false);
+        assert_eq!(interleaved.fetch().unwrap(), "a 1 b 2 c");
+    }
+
+    #[test]
+    fn failing_combined_source_is_skipped_if_configured() {
+        let sources = || vec![Source::from_text("words".to_string()), failing()];
+
+        let mut skipping = combined(sources(), SourceOrder::Sequential, true);
+        assert_eq!(skipping.fetch().unwrap(), "words");
+
+        let mut strict = combined(sources(), SourceOrder::Sequential, false);
+        assert!(matches!(strict.fetch(), Err(FetchError::SourceError(_))));
+
+        let mut all_failing = combined(vec![failing(), failing()], SourceOrder::Sequential, true);
+        assert!(matches!(
+            all_failing.fetch(),
+            Err(FetchError::SourceError(_))
+        ));
+    }
+
+    #[test]
+    fn same_seed_produces_same_text() {
+        let seeded = |seed| Source {
+            generator: Generator::List {
+                words: (0..50).map(|number| number.to_string()).collect(),
+                randomize: true,
+            },
+            transforms: vec![Transform::DigitsAndSymbols],
+            rng: StdRng::seed_from_u64(seed),
+        };
+
+        let first = seeded(42).fetch().unwrap();
+        assert_eq!(first, seeded(42).fetch().unwrap());
+        assert_ne!(first, seeded(43).fetch().unwrap());
+    }
+
+    #[test]
+    fn combined_sources_get_their_own_seed() {
+        let numbers = create_default_sources().remove("number_words").unwrap();
+        let mut source = Source::from_configs(
+            &Config::default(),
+            vec![numbers.clone(), numbers],
+            &std::iter::empty().collect::<ParameterValues>(),
+            42,
+        )
+        .unwrap();
+
+        let text = source.fetch().unwrap();
+        let words = text.split(' ').collect::<Vec<_>>();
+        let (first, second) = words.split_at(words.len() / 2);
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn common_words_are_sampled_from_the_top_ranks() {
+        let definition = GeneratorDefinition::CommonWords {
+            frequency_rank_max: ConditionValue::Number(10),
+            words: ConditionValue::String("{words}".to_string()),
+        };
+        let word_count = Definition::FixedNumber(200).into_parameter(false).unwrap();
+        let parameters =
+            std::iter::once(("words".to_string(), word_count)).collect::<ParameterValues>();
+        let mut source = Source {
+            generator: Generator::from_config(&Config::default(), "", definition, &parameters)
+                .unwrap(),
+            transforms: Vec::new(),
+            rng: StdRng::seed_from_u64(0),
+        };
+
+        let top = COMMON_WORDS.split_whitespace().take(10).collect::<Vec<_>>();
+        let text = source.fetch().unwrap();
+        assert_eq!(text.split(' ').count(), 200);
+        assert!(text.split(' ').all(|word| top.contains(&word)));
+        // The most common word is picked the most often
+        let count = |word| text.split(' ').filter(|other| *other == word).count();
+        assert!(count(top[0]) > count(top[9]));
+    }
+
+    #[test]
+    fn invalid_utf8_output_is_rejected() {
+        assert_eq!(decode_output("héllo".as_bytes().to_vec()).unwrap(), "héllo");
+        assert!(matches!(
+            decode_output(vec![b'h', 0xFF, b'i']),
+            Err(FetchError::Output(_))
+        ));
+        assert!(matches!(
+            decode_output("h\u{FFFD}i".as_bytes().to_vec()),
+            Err(FetchError::SourceError(_))
+        ));
+    }
+}
@@ -1,21 +1,44 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, path::PathBuf};
 
-use crossterm::event::{Event, KeyCode};
-use gladius::{CharacterResult, statistics::Statistics};
+use crossterm::event::{Event, KeyCode, KeyEvent};
+use gladius::{
+    CharacterResult, State, TypingSession,
+    statistics::{Input, Statistics},
+};
 use ratatui::{
     Frame,
     layout::{Constraint, Layout, Rect},
     style::{Color, Style, Stylize},
     text::{Line, Span, ToSpan},
     widgets::{
-        Axis, Block, Borders, Chart, Dataset, GraphType, LegendPosition, Padding, Paragraph,
+        Axis, Bar, BarChart, BarGroup, Block, Borders, Chart, Dataset, GraphType, LegendPosition,
+        Padding, Paragraph,
     },
 };
 
-use crate::{app::Message, config::Config, utils::ROUNDED_BLOCK};
+use crate::{
+    app::Message,
+    config::{
+        Config,
+        keymap::Action,
+        stats_layout::{StatsChart, StatsPanel},
+    },
+    page::{Review, Session, has_command_modifier, session::Mode},
+    statistics::{SessionConfig, SessionExport, StatisticsError, StatsDiff},
+    utils::ROUNDED_BLOCK,
+};
 
 type PlotData = Vec<(f64, f64)>;
 
+/// Characters per word in the standard WPM model
+const CHARS_PER_WORD: f64 = 5.0;
+/// How many of the slowest words are listed
+const SLOWEST_WORDS: usize = 5;
+/// Width of a bucket of the keystroke interval histogram, in milliseconds
+const LATENCY_BUCKET_MS: u64 = 50;
+/// Buckets of the keystroke interval histogram. The last one holds all longer pauses
+const LATENCY_BUCKETS: usize = 12;
+
 /// Page: Stats
 ///
 /// Contains data and logic to show statistics after a session.
@@ -27,6 +50,26 @@ pub struct Stats {
     wpm_low: f64,
     wpm_high: f64,
     char_errors: BTreeMap<usize, Vec<char>>,
+    session_config: SessionConfig,
+    status: Option<String>,
+    show_keystrokes: bool,
+    baseline: Option<StatsDiff>,
+    /// Show Wpm and time. Accuracy is always shown
+    show_speed: bool,
+    /// Whether the session beat the Wpm of every previous run of the same mode and source
+    personal_best: bool,
+    /// The finished session, kept around to review the typed text
+    review_session: Option<TypingSession>,
+    /// Words and the seconds spent on them, slowest first
+    slowest_words: Vec<(String, f64)>,
+    /// Typed words with a mistake, in order of appearance
+    failed_words: Vec<String>,
+    /// Id of the session in the history, which the tags are saved to
+    saved_session: Option<String>,
+    /// Notes attached to the session, like "tired"
+    tags: Vec<String>,
+    /// The tag being typed, while editing the tags
+    tag_input: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -37,10 +80,13 @@ pub struct DataSets {
     raw_accuracy: PlotData,
     actual_accuracy: PlotData,
     consistency: PlotData,
+    keystroke_wpm: PlotData,
+    /// Keystrokes per interval bucket, see [`latency_histogram`]
+    latency: Vec<usize>,
 }
 
-impl From<Statistics> for Stats {
-    fn from(value: Statistics) -> Self {
+impl Stats {
+    pub fn new(value: Statistics, session_config: SessionConfig) -> Self {
         let measurements_len = value.measurements.len();
         let mut raw_wpm = Vec::with_capacity(measurements_len);
         let mut actual_wpm = Vec::with_capacity(measurements_len);
@@ -68,7 +114,15 @@ impl From<Statistics> for Stats {
             }
         });
 
+        let timestamps = value
+            .input_history
+            .iter()
+            .map(|input| input.timestamp)
+            .collect::<Vec<_>>();
+
         let datasets = DataSets {
+            keystroke_wpm: keystroke_wpm(&timestamps),
+            latency: latency_histogram(&timestamps, LATENCY_BUCKET_MS, LATENCY_BUCKETS),
             errors,
             raw_wpm,
             actual_wpm,
@@ -95,105 +149,287 @@ impl From<Statistics> for Stats {
             wpm_low,
             wpm_high,
             char_errors,
+            session_config,
+            status: None,
+            show_keystrokes: false,
+            baseline: None,
+            show_speed: true,
+            personal_best: false,
+            review_session: None,
+            failed_words: Vec::new(),
+            slowest_words: Vec::new(),
+            saved_session: None,
+            tags: Vec::new(),
+            tag_input: None,
+        }
+    }
+
+    /// Sets a note shown in the top bar
+    pub fn with_status(mut self, status: String) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Sets the difference to the previous run of the same mode and source
+    pub const fn with_baseline(mut self, diff: StatsDiff) -> Self {
+        self.baseline = Some(diff);
+        self
+    }
+
+    /// Hides Wpm and time, for modes that are only about accuracy
+    pub const fn without_speed(mut self) -> Self {
+        self.show_speed = false;
+        self
+    }
+
+    /// Celebrates a new personal best
+    pub const fn with_personal_best(mut self) -> Self {
+        self.personal_best = true;
+        self
+    }
+
+    /// Sets the id the session was saved to the history with, so tags can be attached to it
+    pub fn with_saved_session(mut self, session_id: String) -> Self {
+        self.saved_session = Some(session_id);
+        self
+    }
+
+    /// Whether a tag is being typed, which typed characters go to
+    pub const fn is_editing_tags(&self) -> bool {
+        self.tag_input.is_some()
+    }
+
+    /// Keeps the finished session, so its text can be reviewed, and its slowest and failed words
+    /// listed
+    pub fn with_review(mut self, gladius_session: TypingSession) -> Self {
+        let characters = (0..gladius_session.text_len())
+            .filter_map(|index| gladius_session.get_character(index))
+            .map(|character| (character.char, character.state))
+            .collect::<Vec<_>>();
+        self.failed_words = failed_words(&characters);
+
+        let text = characters
+            .iter()
+            .map(|(character, _)| *character)
+            .collect::<Vec<_>>();
+        let mut words = word_durations(&text, &self.gladius_stats.input_history);
+        words.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        words.truncate(SLOWEST_WORDS);
+        self.slowest_words = words;
+
+        self.review_session = Some(gladius_session);
+        self
+    }
+
+    /// Writes the tags to the saved session
+    fn save_tags(&self, config: &Config) -> Option<Message> {
+        let (Some(stats_manager), Some(session_id)) =
+            (&config.statistics_manager, &self.saved_session)
+        else {
+            return None;
+        };
+        stats_manager
+            .set_tags(session_id, &self.tags)
+            .err()
+            .map(|error| Message::Error(Box::new(error)))
+    }
+
+    /// Handles keys while editing the tags. Enter adds the typed tag, or finishes editing when
+    /// nothing is typed. Backspace on an empty tag removes the last one
+    fn handle_tag_key(&mut self, key: &KeyEvent, config: &Config) -> Option<Message> {
+        let input = self.tag_input.as_mut()?;
+
+        match key.code {
+            KeyCode::Char(character) if !has_command_modifier(key) => input.push(character),
+            KeyCode::Backspace => {
+                // With nothing typed, the last tag is removed
+                if input.pop().is_some() || self.tags.pop().is_none() {
+                    return None;
+                }
+                return self.save_tags(config);
+            }
+            KeyCode::Enter => {
+                let tag = std::mem::take(input);
+                if tag.trim().is_empty() {
+                    self.tag_input = None;
+                } else if add_tag(&mut self.tags, &tag) {
+                    return self.save_tags(config);
+                }
+            }
+            _ => (),
         }
+
+        None
+    }
+
+    /// Exports the full session as JSON into the configured exports directory
+    pub fn export(&self, config: &Config) -> Result<PathBuf, StatisticsError> {
+        let export = SessionExport::new(self.session_config.clone(), &self.gladius_stats);
+        if config.settings.statistic.store_input_history {
+            export
+        } else {
+            export.without_input_history()
+        }
+        .write(config.exports_dir())
     }
 }
 
 // Rendering logic
 impl Stats {
     pub fn render(&self, frame: &mut Frame, area: Rect, config: &Config) {
-        let [text, charts] =
-            Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)])
-                .areas(area);
-
-        let [wpm, accuracy] =
-            Layout::vertical([Constraint::Percentage(40), Constraint::Percentage(60)])
-                .areas(charts);
+        let layout = &config.settings.stats_layout;
+        // Speed is left out entirely in modes that hide it
+        let panels = layout
+            .panels
+            .iter()
+            .filter(|panel| self.show_speed || **panel != StatsPanel::SlowestWords)
+            .collect::<Vec<_>>();
+        let charts = layout
+            .charts
+            .iter()
+            .filter(|chart| {
+                self.show_speed || !matches!(chart, StatsChart::Wpm | StatsChart::Latency)
+            })
+            .collect::<Vec<_>>();
+
+        let panels_width = if charts.is_empty() {
+            100
+        } else if panels.is_empty() {
+            0
+        } else {
+            layout.panels_width.min(100)
+        };
+        let [text, charts_area] = Layout::horizontal([
+            Constraint::Percentage(panels_width),
+            Constraint::Percentage(100 - panels_width),
+        ])
+        .areas(area);
 
         let text_area = Block::new().padding(Padding::right(1)).inner(text);
+        let panel_areas = Layout::vertical(panels.iter().map(|panel| match panel {
+            StatsPanel::Summary => Constraint::Length(12),
+            StatsPanel::FailedCharacters | StatsPanel::SlowestWords => Constraint::Fill(1),
+        }))
+        .split(text_area);
+        for (panel, area) in panels.into_iter().zip(panel_areas.iter()) {
+            match panel {
+                StatsPanel::Summary => self.render_summary(frame, *area, config),
+                StatsPanel::FailedCharacters => self.render_failed_characters(frame, *area, config),
+                StatsPanel::SlowestWords => self.render_slowest_words(frame, *area, config),
+            }
+        }
 
-        let [summary, characters] =
-            Layout::vertical([Constraint::Length(10), Constraint::Fill(1)]).areas(text_area);
+        let chart_areas = Layout::vertical(charts.iter().map(|chart| match chart {
+            StatsChart::Wpm => Constraint::Fill(2),
+            StatsChart::Accuracy => Constraint::Fill(3),
+            StatsChart::Latency => Constraint::Fill(2),
+        }))
+        .split(charts_area);
+        for (chart, area) in charts.into_iter().zip(chart_areas.iter()) {
+            match chart {
+                StatsChart::Wpm => self.render_wpm_chart(frame, *area, config),
+                StatsChart::Accuracy => self.render_accuracy_chart(frame, *area, config),
+                StatsChart::Latency => self.render_latency_chart(frame, *area, config),
+            }
+        }
+    }
 
+    fn render_wpm_chart(&self, frame: &mut Frame, area: Rect, config: &Config) {
         let theme = &config.settings.theme.plot;
-
         let total_duration = self.gladius_stats.duration.as_secs_f64();
 
         let raw_wpm = Dataset::default()
-            .name("Raw Wpm")
+            .name(config.tr("stats.raw_wpm"))
             .marker(theme.line_symbol.as_marker())
             .graph_type(GraphType::Line)
             .style(Style::default().fg(theme.raw_wpm))
             .data(&self.datasets.raw_wpm);
 
         let actual_wpm = Dataset::default()
-            .name("Wpm")
+            .name(config.tr("stats.wpm"))
             .marker(theme.line_symbol.as_marker())
             .graph_type(GraphType::Line)
             .style(Style::default().fg(theme.actual_wpm))
             .data(&self.datasets.actual_wpm);
 
+        let mut wpm_datasets = vec![raw_wpm, actual_wpm];
+        if self.show_keystrokes {
+            wpm_datasets.push(
+                Dataset::default()
+                    .name(config.tr("stats.keystroke_wpm"))
+                    .marker(theme.scatter_symbol.as_marker())
+                    .graph_type(GraphType::Scatter)
+                    .style(Style::default().fg(theme.raw_wpm))
+                    .data(&self.datasets.keystroke_wpm),
+            );
+        }
+
+        let wpm_chart = Chart::new(wpm_datasets)
+            .block(ROUNDED_BLOCK.title(Span::from(config.tr("stats.words_per_minute")).bold()))
+            .x_axis(
+                Axis::default()
+                    .title(config.tr("stats.time"))
+                    .style(Style::default().fg(Color::Gray))
+                    .labels([
+                        1.0f64.trunc().to_string(),
+                        total_duration.trunc().to_string(),
+                    ])
+                    .bounds([1.0, total_duration]),
+            )
+            .y_axis(
+                Axis::default()
+                    .style(Style::default().fg(Color::Gray))
+                    .labels([
+                        self.wpm_low.trunc().to_string(),
+                        ((self.wpm_high + self.wpm_low) / 2.0).trunc().to_string(),
+                        (self.wpm_high).trunc().to_string(),
+                    ])
+                    .bounds([self.wpm_low, self.wpm_high]),
+            )
+            .legend_position(Some(LegendPosition::BottomRight));
+
+        frame.render_widget(wpm_chart, area);
+    }
+
+    fn render_accuracy_chart(&self, frame: &mut Frame, area: Rect, config: &Config) {
+        let theme = &config.settings.theme.plot;
+        let total_duration = self.gladius_stats.duration.as_secs_f64();
+
         let errors = Dataset::default()
-            .name("Errors")
+            .name(config.tr("stats.errors"))
             .marker(theme.scatter_symbol.as_marker())
             .graph_type(GraphType::Scatter)
             .style(Style::default().fg(theme.errors))
             .data(&self.datasets.errors);
 
         let raw_accuracy = Dataset::default()
-            .name("Raw Accuracy")
+            .name(config.tr("stats.raw_accuracy"))
             .marker(theme.line_symbol.as_marker())
             .graph_type(GraphType::Line)
             .style(Style::default().fg(theme.actual_wpm))
             .data(&self.datasets.raw_accuracy);
 
         let actual_accuracy = Dataset::default()
-            .name("Accuracy")
+            .name(config.tr("stats.accuracy"))
             .marker(theme.line_symbol.as_marker())
             .graph_type(GraphType::Line)
             .style(Style::default().fg(theme.accuracy))
             .data(&self.datasets.actual_accuracy);
 
         let consistency = Dataset::default()
-            .name("Consistency")
+            .name(config.tr("stats.consistency"))
             .marker(theme.line_symbol.as_marker())
             .graph_type(GraphType::Line)
             .style(Style::default().fg(Color::Blue))
             .data(&self.datasets.consistency);
 
-        let wpm_chart = Chart::new(vec![raw_wpm, actual_wpm])
-            .block(ROUNDED_BLOCK.title("Words/min".to_span().bold()))
-            .x_axis(
-                Axis::default()
-                    .title("Time")
-                    .style(Style::default().fg(Color::Gray))
-                    .labels([
-                        1.0f64.trunc().to_string(),
-                        total_duration.trunc().to_string(),
-                    ])
-                    .bounds([1.0, total_duration]),
-            )
-            .y_axis(
-                Axis::default()
-                    .style(Style::default().fg(Color::Gray))
-                    .labels([
-                        self.wpm_low.trunc().to_string(),
-                        ((self.wpm_high + self.wpm_low) / 2.0).trunc().to_string(),
-                        (self.wpm_high).trunc().to_string(),
-                    ])
-                    .bounds([self.wpm_low, self.wpm_high]),
-            )
-            .legend_position(Some(LegendPosition::BottomRight));
-
-        frame.render_widget(wpm_chart, wpm);
-
         let accuracy_chart = Chart::new(vec![consistency, raw_accuracy, actual_accuracy, errors])
-            .block(ROUNDED_BLOCK.title("Accuracy".to_span().bold()))
+            .block(ROUNDED_BLOCK.title(Span::from(config.tr("stats.accuracy")).bold()))
             .x_axis(
                 Axis::default()
-                    .title("Time")
+                    .title(config.tr("stats.time"))
                     .style(Style::default().fg(Color::Gray))
-                    .labels(["start", "end"])
+                    .labels([config.tr("stats.start"), config.tr("stats.end")])
                     .bounds([1.0, total_duration]),
             )
             .y_axis(
@@ -204,44 +440,156 @@ impl Stats {
             )
             .legend_position(Some(LegendPosition::BottomRight));
 
-        frame.render_widget(accuracy_chart, accuracy);
-
-        let summary_text = Paragraph::new(vec![
-            Line::from(format!("Time (Minutes): {:.2}", total_duration / 60.0)),
-            Line::from(format!(
-                "Wpm (Actual)  : {:.2}",
-                self.gladius_stats.wpm.actual
-            )),
-            Line::from(format!("Wpm (Raw)     : {:.2}", self.gladius_stats.wpm.raw)),
-            Line::from(format!(
-                "Accuracy      : {}%",
-                self.gladius_stats.accuracy.actual.trunc()
-            )),
-            Line::from(format!(
-                "Consistency   : {}%",
-                self.gladius_stats.consistency.actual_percent.trunc()
-            )),
-            Line::from(format!(
-                "Deletions     : {} ({} wrong)",
-                self.gladius_stats.counters.deletes, self.gladius_stats.counters.wrong_deletes
-            )),
-            Line::from(format!(
-                "Errors        : {}",
-                self.gladius_stats.counters.errors
-            )),
-            Line::from(format!(
-                "Corrections   : {}",
-                self.gladius_stats.counters.corrections
-            )),
-        ])
-        .block(
-            ROUNDED_BLOCK
+        frame.render_widget(accuracy_chart, area);
+    }
+
+    fn render_latency_chart(&self, frame: &mut Frame, area: Rect, config: &Config) {
+        let theme = &config.settings.theme.plot;
+
+        let last = self.datasets.latency.len().saturating_sub(1);
+        let bars = self
+            .datasets
+            .latency
+            .iter()
+            .enumerate()
+            .map(|(bucket, count)| {
+                let start = bucket as u64 * LATENCY_BUCKET_MS;
+                let label = if bucket == last {
+                    format!("{start}+")
+                } else {
+                    start.to_string()
+                };
+                Bar::default().value(*count as u64).label(Line::from(label))
+            })
+            .collect::<Vec<_>>();
+
+        // Bars share the inner width of the block, with a gap of one between them
+        let buckets = u16::try_from(bars.len().max(1)).unwrap_or(u16::MAX);
+        let bar_width = (area.width.saturating_sub(2) / buckets)
+            .saturating_sub(1)
+            .max(1);
+
+        let latency_chart = BarChart::default()
+            .block(ROUNDED_BLOCK.title(Span::from(config.tr("stats.keystroke_intervals")).bold()))
+            .bar_width(bar_width)
+            .bar_gap(1)
+            .bar_style(Style::default().fg(theme.actual_wpm))
+            .data(BarGroup::default().bars(&bars));
+
+        frame.render_widget(latency_chart, area);
+    }
+
+    fn render_summary(&self, frame: &mut Frame, area: Rect, config: &Config) {
+        let total_duration = self.gladius_stats.duration.as_secs_f64();
+
+        let efficiency =
+            keystroke_efficiency(self.gladius_stats.ipm.actual, self.gladius_stats.ipm.raw);
+
+        let mut summary_rows = Vec::new();
+        if self.show_speed {
+            summary_rows.extend([
+                (
+                    config.tr("stats.time_minutes"),
+                    format!("{:.2}", total_duration / 60.0),
+                ),
+                (
+                    config.tr("stats.wpm_actual"),
+                    format!("{:.2}", self.gladius_stats.wpm.actual),
+                ),
+                (
+                    config.tr("stats.wpm_raw"),
+                    format!("{:.2}", self.gladius_stats.wpm.raw),
+                ),
+            ]);
+        }
+        summary_rows.extend([
+            (
+                config.tr("stats.accuracy"),
+                format!("{}%", self.gladius_stats.accuracy.actual.trunc()),
+            ),
+            (
+                config.tr("stats.consistency"),
+                format!("{}%", self.gladius_stats.consistency.actual_percent.trunc()),
+            ),
+            (
+                config.tr("stats.deletions"),
+                config.tr_with(
+                    "stats.deletions_value",
+                    &[
+                        ("deletes", &self.gladius_stats.counters.deletes),
+                        ("wrong", &self.gladius_stats.counters.wrong_deletes),
+                    ],
+                ),
+            ),
+            (
+                config.tr("stats.errors"),
+                self.gladius_stats.counters.errors.to_string(),
+            ),
+            (
+                config.tr("stats.corrections"),
+                self.gladius_stats.counters.corrections.to_string(),
+            ),
+            (
+                config.tr("stats.efficiency"),
+                config.tr_with(
+                    "stats.efficiency_value",
+                    &[
+                        ("efficiency", &format!("{:.0}", efficiency * 100.0)),
+                        ("wasted", &format!("{:.0}", (1.0 - efficiency) * 100.0)),
+                    ],
+                ),
+            ),
+        ]);
+        if !self.tags.is_empty() {
+            summary_rows.push((config.tr("stats.tags"), self.tags.join(", ")));
+        }
+        if let Some(diff) = self.baseline {
+            let accuracy = format!("{:+.2}", diff.accuracy_actual);
+            let value = if self.show_speed {
+                config.tr_with(
+                    "stats.vs_last_run_value",
+                    &[
+                        ("wpm", &format!("{:+.2}", diff.wpm_actual)),
+                        ("accuracy", &accuracy),
+                    ],
+                )
+            } else {
+                config.tr_with("stats.vs_last_run_accuracy", &[("accuracy", &accuracy)])
+            };
+            summary_rows.push((config.tr("stats.vs_last_run"), value));
+        }
+
+        // Labels are padded to the longest one, so the values line up in any language
+        let label_width = summary_rows
+            .iter()
+            .map(|(label, _)| label.chars().count())
+            .max()
+            .unwrap_or_default();
+        let summary_lines = summary_rows
+            .into_iter()
+            .map(|(label, value)| Line::from(format!("{label:<label_width$}: {value}")))
+            .collect::<Vec<_>>();
+
+        let summary_text = Paragraph::new(summary_lines).block({
+            let block = ROUNDED_BLOCK
                 .borders(Borders::TOP)
-                .title("Summary".to_span().bold()),
-        );
+                .title(Span::from(config.tr("stats.summary")).bold());
+            // Personal bests are about Wpm
+            if self.personal_best && self.show_speed {
+                block.title(
+                    Span::from(config.tr("stats.personal_best"))
+                        .bold()
+                        .fg(config.settings.theme.text.success),
+                )
+            } else {
+                block
+            }
+        });
 
-        frame.render_widget(summary_text, summary);
+        frame.render_widget(summary_text, area);
+    }
 
+    fn render_failed_characters(&self, frame: &mut Frame, area: Rect, config: &Config) {
         let character_lines: Vec<Line> = self
             .char_errors
             .iter()
@@ -261,23 +609,371 @@ impl Stats {
         let character_errors = Paragraph::new(character_lines).block(
             ROUNDED_BLOCK
                 .borders(Borders::TOP)
-                .title("Failed characters".to_span().bold()),
+                .title(Span::from(config.tr("stats.failed_characters")).bold()),
+        );
+
+        frame.render_widget(character_errors, area);
+    }
+
+    fn render_slowest_words(&self, frame: &mut Frame, area: Rect, config: &Config) {
+        let word_lines: Vec<Line> = self
+            .slowest_words
+            .iter()
+            .map(|(word, seconds)| {
+                Line::default().spans(vec![
+                    word.to_span().style(Style::new().bold()),
+                    Span::from(format!(": {seconds:.2}s")),
+                ])
+            })
+            .collect();
+
+        let slowest_words = Paragraph::new(word_lines).block(
+            ROUNDED_BLOCK
+                .borders(Borders::TOP)
+                .title(Span::from(config.tr("stats.slowest_words")).bold()),
         );
 
-        frame.render_widget(character_errors, characters);
+        frame.render_widget(slowest_words, area);
     }
 
-    pub fn render_top(&self, _config: &Config) -> Option<Line<'_>> {
-        Some(Line::raw("<Enter> to go back to the menu"))
+    pub fn render_top(&self, config: &Config) -> Option<Line<'_>> {
+        if let Some(input) = &self.tag_input {
+            return Some(Line::raw(config.tr_with(
+                "stats.editing_tags",
+                &[("tags", &self.tags.join(", ")), ("input", input)],
+            )));
+        }
+
+        let mut keys = config.tr("stats.keys").to_string();
+        if !self.failed_words.is_empty() {
+            keys.push_str(config.tr("stats.practice_key"));
+        }
+        if self.saved_session.is_some() {
+            keys.push_str(config.tr("stats.tags_key"));
+        }
+        Some(Line::raw(self.status.as_ref().map_or_else(
+            || keys.to_string(),
+            |status| format!("{keys} | {status}"),
+        )))
     }
 
-    pub fn handle_events(&self, event: &Event, _config: &Config) -> Option<Message> {
+    pub fn handle_events(&mut self, event: &Event, config: &Config) -> Option<Message> {
+        let keys = &config.settings.keybindings;
         if let Event::Key(key) = event
-            && key.code == KeyCode::Enter
+            && key.is_press()
         {
-            return Some(Message::Reset);
+            if self.is_editing_tags() {
+                return self.handle_tag_key(key, config);
+            }
+
+            match key.code {
+                _ if keys.is(Action::Select, key) => return Some(Message::Reset),
+                _ if keys.is(Action::Export, key) => match self.export(config) {
+                    Ok(path) => {
+                        self.status =
+                            Some(config.tr_with("stats.exported", &[("path", &path.display())]));
+                    }
+                    Err(error) => return Some(Message::Error(Box::new(error))),
+                },
+                _ if keys.is(Action::Keystrokes, key) => {
+                    self.show_keystrokes = !self.show_keystrokes
+                }
+                _ if keys.is(Action::Tags, key) && self.saved_session.is_some() => {
+                    self.tag_input = Some(String::new());
+                }
+                _ if keys.is(Action::Practice, key) && !self.failed_words.is_empty() => {
+                    let text = self.failed_words.join(" ");
+                    return Some(match Session::new(config, Mode::from_text(text)) {
+                        Ok(session) => Message::Show(session.into()),
+                        Err(error) => Message::Error(Box::new(error)),
+                    });
+                }
+                _ if keys.is(Action::Review, key) => {
+                    if let Some(gladius_session) = &self.review_session {
+                        let review = Review::new(gladius_session.clone());
+                        return Some(Message::Push(review.into()));
+                    }
+                }
+                _ => (),
+            }
         }
 
         None
     }
 }
+
+/// Computes the instantaneous WPM of every keystroke, from the interval since the previous one
+fn keystroke_wpm(timestamps: &[f64]) -> PlotData {
+    timestamps
+        .windows(2)
+        .filter_map(|pair| {
+            let latency = pair[1] - pair[0];
+            (latency > 0.0).then(|| (pair[1], 60.0 / (latency * CHARS_PER_WORD)))
+        })
+        .collect()
+}
+
+/// Counts the intervals between keystrokes into `n_buckets` buckets of `bucket_ms` milliseconds.
+///
+/// The last bucket holds every interval that doesn't fit into the others, so pauses still show
+/// up.
+fn latency_histogram(timestamps: &[f64], bucket_ms: u64, n_buckets: usize) -> Vec<usize> {
+    let mut buckets = vec![0; n_buckets];
+    let Some(last) = n_buckets.checked_sub(1) else {
+        return buckets;
+    };
+
+    for pair in timestamps.windows(2) {
+        let millis = ((pair[1] - pair[0]).max(0.0) * 1000.0).round() as u64;
+        let bucket = usize::try_from(millis / bucket_ms.max(1)).unwrap_or(usize::MAX);
+        buckets[bucket.min(last)] += 1;
+    }
+
+    buckets
+}
+
+/// Adds `tag` without surrounding whitespace, unless it's empty or already there. Returns whether
+/// it was added
+fn add_tag(tags: &mut Vec<String>, tag: &str) -> bool {
+    let tag = tag.trim();
+    if tag.is_empty() || tags.iter().any(|existing| existing == tag) {
+        return false;
+    }
+    tags.push(tag.to_string());
+    true
+}
+
+/// Returns the typed words that had a mistake, whether it was corrected or not
+fn failed_words(characters: &[(char, State)]) -> Vec<String> {
+    characters
+        .split(|(character, _)| character.is_whitespace())
+        .filter(|word| {
+            word.iter().any(|(_, state)| {
+                matches!(state, State::Wrong | State::WasWrong | State::Corrected)
+            })
+        })
+        .map(|word| word.iter().map(|(character, _)| character).collect())
+        .collect()
+}
+
+/// Computes the seconds spent on every word of `text`, in order of appearance.
+///
+/// Each keystroke is charged the time since the previous one, and belongs to the word at the
+/// position it typed or deleted. Corrections therefore count towards the word they fixed.
+fn word_durations(text: &[char], inputs: &[Input]) -> Vec<(String, f64)> {
+    // The word each position belongs to, if any
+    let mut words: Vec<(String, f64)> = Vec::new();
+    let mut word_at = Vec::with_capacity(text.len());
+    let mut in_word = false;
+    for character in text {
+        if character.is_whitespace() {
+            in_word = false;
+            word_at.push(None);
+            continue;
+        }
+        if !in_word {
+            words.push((String::new(), 0.0));
+            in_word = true;
+        }
+        let index = words.len() - 1;
+        words[index].0.push(*character);
+        word_at.push(Some(index));
+    }
+
+    let mut position = 0usize;
+    let mut previous = None;
+    for input in inputs {
+        let index = if let CharacterResult::Deleted(_) = input.result {
+            position = position.saturating_sub(1);
+            position
+        } else {
+            position += 1;
+            position - 1
+        };
+
+        let latency = previous.map_or(0.0, |previous| input.timestamp - previous);
+        previous = Some(input.timestamp);
+
+        if let Some(Some(word)) = word_at.get(index) {
+            words[*word].1 += latency;
+        }
+    }
+
+    words
+}
+
+/// Share of keystrokes that were productive, from the actual and raw inputs per minute.
+///
+/// Sessions without keystrokes count as fully efficient.
+fn keystroke_efficiency(ipm_actual: f64, ipm_raw: f64) -> f64 {
+    if ipm_raw <= 0.0 {
+        return 1.0;
+    }
+
+    (ipm_actual / ipm_raw).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+    use gladius::{CharacterResult, State, TypingSession, statistics::Input};
+
+    use crate::{config::Config, statistics::SessionConfig};
+
+    use super::{
+        Stats, add_tag, failed_words, keystroke_efficiency, keystroke_wpm, latency_histogram,
+        word_durations,
+    };
+
+    #[test]
+    fn keystroke_wpm_from_intervals() {
+        // 0.2s per keystroke = 5 keystrokes per second = 60 wpm
+        let timestamps = [1.0, 1.2, 1.4, 1.4, 2.4];
+
+        let data = keystroke_wpm(&timestamps);
+
+        assert_eq!(data.len(), 3);
+        assert!((data[0].1 - 60.0).abs() < 1e-9);
+        assert!((data[1].1 - 60.0).abs() < 1e-9);
+        assert!((data[2].1 - 12.0).abs() < 1e-9);
+        assert_eq!(data[2].0, 2.4);
+    }
+
+    #[test]
+    fn tags_are_trimmed_and_unique() {
+        let mut tags = Vec::new();
+
+        assert!(add_tag(&mut tags, " tired "));
+        assert!(add_tag(&mut tags, "left-hand drill"));
+        assert!(!add_tag(&mut tags, "tired"));
+        assert!(!add_tag(&mut tags, "  "));
+        assert_eq!(tags, ["tired", "left-hand drill"]);
+    }
+
+    #[test]
+    fn tags_are_entered_and_removed_with_keys() {
+        let config = Config::default();
+        let mut typing = TypingSession::new("abc").unwrap();
+        for character in "abc".chars() {
+            typing.input(Some(character));
+        }
+        let session_config = SessionConfig {
+            mode_name: "Text".to_string(),
+            source_name: "Custom text".to_string(),
+            time_limit: None,
+            words_typed_limit: None,
+            allow_deletions: true,
+            allow_errors: true,
+        };
+        let mut stats =
+            Stats::new(typing.finalize(), session_config).with_saved_session("id".to_string());
+        let press = |stats: &mut Stats, keys: &str| {
+            keys.chars()
+                .map(|key| {
+                    let code = match key {
+                        '\n' => KeyCode::Enter,
+                        '\u{8}' => KeyCode::Backspace,
+                        character => KeyCode::Char(character),
+                    };
+                    stats.handle_events(
+                        &Event::Key(KeyEvent::new(code, KeyModifiers::NONE)),
+                        &config,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        press(&mut stats, "t");
+        assert!(stats.is_editing_tags());
+        // Enter adds the tag instead of going back to the menu
+        let messages = press(&mut stats, "tired\nslow\n");
+        assert!(messages.iter().all(Option::is_none));
+        assert_eq!(stats.tags, ["tired", "slow"]);
+
+        // Backspace deletes the typed tag, then the last one
+        press(&mut stats, "x\u{8}\u{8}");
+        assert_eq!(stats.tags, ["tired"]);
+
+        // Enter without a tag finishes editing
+        press(&mut stats, "\n");
+        assert!(!stats.is_editing_tags());
+        assert_eq!(stats.tags, ["tired"]);
+    }
+
+    #[test]
+    fn latency_histogram_buckets_intervals() {
+        // Intervals of 10, 60, 99, 100 and 2000 ms
+        let timestamps = [0.0, 0.01, 0.07, 0.169, 0.269, 2.269];
+
+        assert_eq!(latency_histogram(&timestamps, 50, 3), [1, 2, 2]);
+        // The pause lands in the last bucket, no matter how long it was
+        assert_eq!(latency_histogram(&timestamps, 50, 5), [1, 2, 1, 0, 1]);
+        assert!(latency_histogram(&timestamps, 50, 0).is_empty());
+        assert_eq!(latency_histogram(&[1.0], 50, 3), [0, 0, 0]);
+    }
+
+    #[test]
+    fn keystroke_efficiency_with_deletions() {
+        // Half of the keystrokes were deletions
+        assert!((keystroke_efficiency(60.0, 120.0) - 0.5).abs() < 1e-9);
+        assert!((keystroke_efficiency(120.0, 120.0) - 1.0).abs() < 1e-9);
+        // Empty session
+        assert!((keystroke_efficiency(0.0, 0.0) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn word_durations_include_corrections() {
+        let text = "one two three".chars().collect::<Vec<_>>();
+        let mut timestamp = 0.0;
+        let mut inputs = Vec::new();
+        let mut type_keys = |keys: &str, interval: f64, result: CharacterResult| {
+            for char in keys.chars() {
+                timestamp += interval;
+                inputs.push(Input {
+                    timestamp,
+                    char,
+                    result,
+                });
+            }
+        };
+
+        type_keys("one ", 0.1, CharacterResult::Correct);
+        // A mistake on 't', deleted and retyped
+        type_keys("x", 0.5, CharacterResult::Wrong);
+        type_keys("\u{8}", 0.5, CharacterResult::Deleted(State::Wrong));
+        type_keys("two ", 0.1, CharacterResult::Correct);
+        type_keys("three", 0.2, CharacterResult::Correct);
+
+        let durations = word_durations(&text, &inputs);
+
+        let words = durations
+            .iter()
+            .map(|(word, _)| word.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(words, ["one", "two", "three"]);
+        // The first keystroke has no previous one to measure from
+        assert!((durations[0].1 - 0.2).abs() < 1e-9);
+        assert!((durations[1].1 - 1.3).abs() < 1e-9);
+        assert!((durations[2].1 - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn failed_words_include_corrected_mistakes() {
+        let typed = |word: &str, state: State| {
+            word.chars()
+                .map(|character| (character, state))
+                .collect::<Vec<_>>()
+        };
+        let mut characters = typed("one ", State::Correct);
+        characters.extend(typed("tw", State::Correct));
+        characters.push(('o', State::Corrected));
+        characters.extend(typed(" ", State::Correct));
+        characters.extend(typed("three ", State::Correct));
+        characters.push(('f', State::Wrong));
+        characters.extend(typed("our ", State::None));
+        characters.extend(typed("five", State::None));
+
+        assert_eq!(failed_words(&characters), ["two", "four"]);
+        assert!(failed_words(&typed("one two", State::Correct)).is_empty());
+    }
+}
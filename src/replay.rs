@@ -0,0 +1,143 @@
+use std::{fs, path::Path, thread::sleep, time::Duration};
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use derive_more::From;
+use thiserror::Error;
+
+use crate::{
+    config::Config,
+    page::session::{FetchError, Mode, Session},
+    statistics::SessionExport,
+};
+
+/// A keystroke of a replay file, and the milliseconds waited before it
+type ReplayEvent = (u64, String);
+
+#[derive(Debug, Error, From)]
+pub enum ReplayError {
+    #[error("Failed to read the replay file: {0}")]
+    Read(std::io::Error),
+
+    #[error("Failed to parse the replay file: {0}")]
+    Parse(serde_json::Error),
+
+    #[error("Unknown key in the replay file: {0:?}")]
+    #[from(ignore)]
+    UnknownKey(String),
+
+    #[error("{0}")]
+    Fetch(FetchError),
+}
+
+/// Loads the keystrokes of a replay file, see [`parse_events`]
+pub fn load_events(path: &Path) -> Result<Vec<(Duration, KeyCode)>, ReplayError> {
+    parse_events(&fs::read_to_string(path)?)
+}
+
+/// Parses a JSON list of `[delay_ms, key]` pairs
+fn parse_events(json: &str) -> Result<Vec<(Duration, KeyCode)>, ReplayError> {
+    serde_json::from_str::<Vec<ReplayEvent>>(json)?
+        .into_iter()
+        .map(|(delay, key)| Ok((Duration::from_millis(delay), parse_key(&key)?)))
+        .collect()
+}
+
+/// Parses a key of a replay file: a single character, `"Enter"` or `"Backspace"`
+fn parse_key(key: &str) -> Result<KeyCode, ReplayError> {
+    let mut characters = key.chars();
+    match (characters.next(), characters.next()) {
+        (Some(character), None) => Ok(KeyCode::Char(character)),
+        _ => match key {
+            "Enter" => Ok(KeyCode::Enter),
+            "Backspace" => Ok(KeyCode::Backspace),
+            _ => Err(ReplayError::UnknownKey(key.to_string())),
+        },
+    }
+}
+
+/// Types `events` into a session of `text`, without the interface and without saving it.
+///
+/// The delays are waited out for real, as the session times the keystrokes itself. Keystrokes
+/// after the end of the session are ignored. Returns the statistics of the session, and whether
+/// it ended.
+pub fn replay(
+    config: &Config,
+    text: String,
+    events: &[(Duration, KeyCode)],
+) -> Result<(SessionExport, bool), ReplayError> {
+    // Only the export may be written to stdout
+    let config = &config.clone().headless();
+    let mut session = Session::new(config, Mode::from_text(text))?;
+
+    for (delay, code) in events {
+        if session.end_reason(config).is_some() {
+            break;
+        }
+        sleep(*delay);
+        let key = Event::Key(KeyEvent::new(*code, KeyModifiers::NONE));
+        session.handle_events(&key, config);
+    }
+
+    let ended = session.end_reason(config).is_some();
+    let export = session.into_export();
+    if config.settings.statistic.store_input_history {
+        Ok((export, ended))
+    } else {
+        Ok((export.without_input_history(), ended))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crossterm::event::KeyCode;
+
+    use crate::config::Config;
+
+    use super::{ReplayError, parse_events, replay};
+
+    #[test]
+    fn events_are_parsed_from_pairs() {
+        let events = parse_events(r#"[[0, "a"], [120, "Backspace"], [80, "Enter"], [5, " "]]"#);
+
+        assert_eq!(
+            events.unwrap(),
+            [
+                (Duration::ZERO, KeyCode::Char('a')),
+                (Duration::from_millis(120), KeyCode::Backspace),
+                (Duration::from_millis(80), KeyCode::Enter),
+                (Duration::from_millis(5), KeyCode::Char(' ')),
+            ]
+        );
+        assert!(matches!(
+            parse_events(r#"[[0, "Tab"]]"#),
+            Err(ReplayError::UnknownKey(key)) if key == "Tab"
+        ));
+        assert!(matches!(
+            parse_events(r#"[[0, ""]]"#),
+            Err(ReplayError::UnknownKey(_))
+        ));
+        assert!(matches!(
+            parse_events(r#"[["a", 0]]"#),
+            Err(ReplayError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn replay_types_the_text() {
+        let config = Config::default();
+        let events =
+            parse_events(r#"[[0, "a"], [0, "x"], [0, "Backspace"], [0, "b"], [0, "c"], [0, "d"]]"#)
+                .unwrap();
+
+        // The last keystroke comes after the end of the session
+        let (export, ended) = replay(&config, "abc".to_string(), &events).unwrap();
+        assert!(ended);
+        assert_eq!(export.statistics.errors, 1);
+        assert_eq!(export.session_config.source_name, "Custom text");
+
+        let (_, ended) = replay(&config, "abcd".to_string(), &events[..3]).unwrap();
+        assert!(!ended);
+    }
+}
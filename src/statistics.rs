@@ -1,12 +1,18 @@
-use gladius::statistics::Statistics;
+use gladius::statistics::{Measurement, Statistics};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 use web_time::SystemTime;
 
 use crate::page::session::Mode;
 
+mod streak;
+
+pub use streak::{StreakTracker, local_day};
+
 #[derive(Debug, Error)]
 pub enum StatisticsError {
     #[error("Failed to create statistics directory: {0}")]
@@ -18,16 +24,77 @@ pub enum StatisticsError {
     #[error("Failed to write statistics file: {0}")]
     WriteFile(std::io::Error),
 
+    #[error("Failed to delete statistics file: {0}")]
+    DeleteFile(std::io::Error),
+
     #[error("Failed to parse statistics: {0}")]
     Parse(serde_json::Error),
 }
 
+/// Version of the schema written by [`StatisticsManager::save_session`]
+pub const SESSION_SCHEMA_VERSION: u32 = 5;
+
+/// Sessions saved before the schema was versioned
+const fn legacy_schema_version() -> u32 {
+    1
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionStatistics {
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
     pub timestamp: SystemTime,
     pub session_id: String,
     pub session_config: SessionConfig,
     pub statistics: SerializableStatistics,
+    /// Amount of errors per character. Empty for sessions saved before schema version 2, or
+    /// without the input history
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub char_errors: HashMap<char, usize>,
+    /// Whether the whole text was typed. `false` for sessions saved before schema version 3
+    #[serde(default)]
+    pub completed: bool,
+    /// Unknown for sessions saved before schema version 3
+    #[serde(default)]
+    pub end_reason: EndReason,
+    /// The measurements taken while typing. Empty for sessions saved before schema version 4
+    #[serde(default)]
+    pub measurements: Vec<SerializableMeasurement>,
+    /// Notes attached after the session, like "tired". Empty for sessions saved before schema
+    /// version 5
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// Why a session ended
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EndReason {
+    /// The whole text was typed
+    Finished,
+    TimeLimit,
+    WordLimit,
+    /// Nothing was typed for the idle timeout. The idle time is part of the duration
+    Idle,
+    /// A mistake was made in a mode that doesn't allow errors
+    ErrorStop,
+    /// Saved before the end reason was recorded
+    #[default]
+    Unknown,
+}
+
+impl EndReason {
+    /// The key of the UI string describing the reason
+    pub const fn key(self) -> &'static str {
+        match self {
+            Self::Finished => "history.end_finished",
+            Self::TimeLimit => "history.end_time_limit",
+            Self::WordLimit => "history.end_word_limit",
+            Self::Idle => "history.end_idle",
+            Self::ErrorStop => "history.end_error",
+            Self::Unknown => "history.end_unknown",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -75,6 +142,201 @@ impl From<&Statistics> for SerializableStatistics {
     }
 }
 
+impl SerializableStatistics {
+    /// Computes the change of every metric from `baseline` to `self`
+    pub fn diff(&self, baseline: &Self) -> StatsDiff {
+        StatsDiff {
+            wpm_actual: self.wpm_actual - baseline.wpm_actual,
+            wpm_raw: self.wpm_raw - baseline.wpm_raw,
+            accuracy_actual: self.accuracy_actual - baseline.accuracy_actual,
+            accuracy_raw: self.accuracy_raw - baseline.accuracy_raw,
+            consistency_actual_percent: self.consistency_actual_percent
+                - baseline.consistency_actual_percent,
+            errors: self.errors as f64 - baseline.errors as f64,
+        }
+    }
+}
+
+/// Signed per-metric deltas between two sessions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StatsDiff {
+    pub wpm_actual: f64,
+    pub wpm_raw: f64,
+    pub accuracy_actual: f64,
+    pub accuracy_raw: f64,
+    pub consistency_actual_percent: f64,
+    pub errors: f64,
+}
+
+/// Lifetime totals over all saved sessions
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AggregateStats {
+    pub session_count: usize,
+    /// Total time spent typing, in seconds
+    pub total_duration: f64,
+    pub total_characters: usize,
+    pub total_errors: usize,
+    pub total_corrections: usize,
+    /// Average WPM, weighted by the duration of each session
+    pub average_wpm: f64,
+    /// Average accuracy, weighted by the duration of each session
+    pub average_accuracy: f64,
+    pub best_wpm: f64,
+    /// Amount of errors per character, merged over all sessions
+    pub char_errors: HashMap<char, usize>,
+}
+
+impl AggregateStats {
+    pub fn from_sessions(sessions: &[SessionStatistics]) -> Self {
+        let mut aggregate = sessions
+            .iter()
+            .fold(Self::default(), |mut aggregate, session| {
+                let statistics = &session.statistics;
+                aggregate.session_count += 1;
+                aggregate.total_duration += statistics.duration;
+                aggregate.total_characters += statistics.adds;
+                aggregate.total_errors += statistics.errors;
+                aggregate.total_corrections += statistics.corrections;
+                aggregate.average_wpm += statistics.wpm_actual * statistics.duration;
+                aggregate.average_accuracy += statistics.accuracy_actual * statistics.duration;
+                aggregate.best_wpm = aggregate.best_wpm.max(statistics.wpm_actual);
+                for (character, count) in &session.char_errors {
+                    *aggregate.char_errors.entry(*character).or_default() += count;
+                }
+                aggregate
+            });
+
+        if aggregate.total_duration > 0.0 {
+            aggregate.average_wpm /= aggregate.total_duration;
+            aggregate.average_accuracy /= aggregate.total_duration;
+        } else {
+            aggregate.average_wpm = 0.0;
+            aggregate.average_accuracy = 0.0;
+        }
+
+        aggregate
+    }
+
+    /// Returns the `limit` most missed characters, most missed first
+    pub fn most_missed(&self, limit: usize) -> Vec<(char, usize)> {
+        let mut missed = self
+            .char_errors
+            .iter()
+            .map(|(character, count)| (*character, *count))
+            .collect::<Vec<_>>();
+        missed.sort_unstable_by(|(a_char, a_count), (b_char, b_count)| {
+            b_count.cmp(a_count).then(a_char.cmp(b_char))
+        });
+        missed.truncate(limit);
+        missed
+    }
+}
+
+/// Version of the schema written by [`SessionExport`]
+pub const EXPORT_SCHEMA_VERSION: u32 = 1;
+
+/// A complete, shareable snapshot of a finished session
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionExport {
+    pub schema_version: u32,
+    pub timestamp: SystemTime,
+    pub session_config: SessionConfig,
+    pub statistics: SerializableStatistics,
+    /// Empty without the input history
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub char_errors: HashMap<char, usize>,
+    pub measurements: Vec<SerializableMeasurement>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializableMeasurement {
+    pub timestamp: f64,
+    pub wpm_actual: f64,
+    pub wpm_raw: f64,
+    pub accuracy_actual: f64,
+    pub accuracy_raw: f64,
+    pub consistency_actual_percent: f64,
+    pub ipm_actual: f64,
+    pub ipm_raw: f64,
+}
+
+impl From<&Measurement> for SerializableMeasurement {
+    fn from(measurement: &Measurement) -> Self {
+        Self {
+            timestamp: measurement.timestamp,
+            wpm_actual: measurement.wpm.actual,
+            wpm_raw: measurement.wpm.raw,
+            accuracy_actual: measurement.accuracy.actual,
+            accuracy_raw: measurement.accuracy.raw,
+            consistency_actual_percent: measurement.consistency.actual_percent,
+            ipm_actual: measurement.ipm.actual,
+            ipm_raw: measurement.ipm.raw,
+        }
+    }
+}
+
+impl SessionExport {
+    pub fn new(session_config: SessionConfig, statistics: &Statistics) -> Self {
+        Self {
+            schema_version: EXPORT_SCHEMA_VERSION,
+            timestamp: SystemTime::now(),
+            session_config,
+            statistics: SerializableStatistics::from(statistics),
+            char_errors: statistics.counters.char_errors.clone(),
+            measurements: statistics
+                .measurements
+                .iter()
+                .map(SerializableMeasurement::from)
+                .collect(),
+        }
+    }
+
+    /// Leaves out which characters were mistyped, keeping only the metrics
+    pub fn without_input_history(mut self) -> Self {
+        self.char_errors.clear();
+        self
+    }
+
+    /// Writes the export to a timestamped file within `directory`, returning the path of the file
+    pub fn write(&self, directory: &Path) -> Result<PathBuf, StatisticsError> {
+        if !directory.exists() {
+            fs::create_dir_all(directory).map_err(StatisticsError::CreateDirectory)?;
+        }
+
+        let millis = self
+            .timestamp
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let json = serde_json::to_string_pretty(self).map_err(StatisticsError::Parse)?;
+
+        // Earlier exports are never overwritten, even if they were made at the same time
+        let mut suffix = 0;
+        loop {
+            let filename = if suffix == 0 {
+                format!("export_{millis}.json")
+            } else {
+                format!("export_{millis}_{suffix}.json")
+            };
+            let file_path = directory.join(filename);
+
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&file_path)
+            {
+                Ok(mut file) => {
+                    file.write_all(json.as_bytes())
+                        .map_err(StatisticsError::WriteFile)?;
+                    return Ok(file_path);
+                }
+                Err(error) if error.kind() == ErrorKind::AlreadyExists => suffix += 1,
+                Err(error) => return Err(StatisticsError::WriteFile(error)),
+            }
+        }
+    }
+}
+
 impl SessionConfig {
     pub fn from_mode(mode: &Mode, mode_name: String, source_name: String) -> Self {
         Self {
@@ -88,9 +350,11 @@ impl SessionConfig {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct StatisticsManager {
     directory: PathBuf,
+    /// Whether saved sessions include which characters were mistyped
+    store_input_history: bool,
 }
 
 impl StatisticsManager {
@@ -98,21 +362,46 @@ impl StatisticsManager {
         if !directory.exists() {
             fs::create_dir_all(&directory).map_err(StatisticsError::CreateDirectory)?;
         }
-        Ok(Self { directory })
+        Ok(Self {
+            directory,
+            store_input_history: true,
+        })
     }
 
+    /// Saves only the metrics of sessions, leaving out which characters were mistyped
+    pub const fn without_input_history(mut self) -> Self {
+        self.store_input_history = false;
+        self
+    }
+
+    /// Saves a finished session, returning its id
     pub fn save_session(
         &self,
         mode: &Mode,
         mode_name: String,
         source_name: String,
         statistics: &Statistics,
-    ) -> Result<(), StatisticsError> {
+        end_reason: EndReason,
+    ) -> Result<String, StatisticsError> {
         let session_stats = SessionStatistics {
+            schema_version: SESSION_SCHEMA_VERSION,
             timestamp: SystemTime::now(),
             session_id: format!("{:?}", SystemTime::now()),
             session_config: SessionConfig::from_mode(mode, mode_name, source_name),
             statistics: SerializableStatistics::from(statistics),
+            char_errors: if self.store_input_history {
+                statistics.counters.char_errors.clone()
+            } else {
+                HashMap::new()
+            },
+            completed: end_reason == EndReason::Finished,
+            end_reason,
+            measurements: statistics
+                .measurements
+                .iter()
+                .map(SerializableMeasurement::from)
+                .collect(),
+            tags: Vec::new(),
         };
 
         let filename = format!(
@@ -123,15 +412,19 @@ impl StatisticsManager {
                 .unwrap_or_default()
                 .as_secs()
         );
-        let file_path = self.directory.join(filename);
+        write_session(&self.directory.join(filename), &session_stats)?;
 
-        let json = serde_json::to_string_pretty(&session_stats).map_err(StatisticsError::Parse)?;
-        fs::write(file_path, json).map_err(StatisticsError::WriteFile)?;
+        self.streak_tracker().record(session_stats.timestamp)?;
 
-        Ok(())
+        Ok(session_stats.session_id)
     }
 
-    pub fn load_all_sessions(&self) -> Result<Vec<SessionStatistics>, StatisticsError> {
+    pub fn streak_tracker(&self) -> StreakTracker {
+        StreakTracker::new(&self.directory)
+    }
+
+    /// Loads every saved session, together with the file it was loaded from
+    fn load_session_files(&self) -> Result<Vec<(PathBuf, SessionStatistics)>, StatisticsError> {
         let mut sessions = Vec::new();
 
         if !self.directory.exists() {
@@ -147,20 +440,63 @@ impl StatisticsManager {
             if path.is_file() && path.extension().is_some_and(|ext| ext == "json") {
                 let content = fs::read_to_string(&path).map_err(StatisticsError::ReadFile)?;
                 match serde_json::from_str::<SessionStatistics>(&content) {
-                    Ok(session) => sessions.push(session),
+                    Ok(session) => sessions.push((path, session)),
                     Err(_) => continue, // Skip invalid files
                 }
             }
         }
 
+        Ok(sessions)
+    }
+
+    pub fn load_all_sessions(&self) -> Result<Vec<SessionStatistics>, StatisticsError> {
+        let mut sessions = self
+            .load_session_files()?
+            .into_iter()
+            .map(|(_, session)| session)
+            .collect::<Vec<_>>();
+
         // Sort by timestamp (newest first)
         sessions.sort_by_key(|b| std::cmp::Reverse(b.timestamp));
 
         Ok(sessions)
     }
 
-    // Allow unused for future use case, as filters would be cool
+    /// Replaces the tags of the saved session with the given id. Returns whether it was found
+    pub fn set_tags(&self, session_id: &str, tags: &[String]) -> Result<bool, StatisticsError> {
+        let Some((path, mut session)) = self
+            .load_session_files()?
+            .into_iter()
+            .find(|(_, session)| session.session_id == session_id)
+        else {
+            return Ok(false);
+        };
+
+        session.tags = tags.to_vec();
+        write_session(&path, &session)?;
+        Ok(true)
+    }
+
+    /// Deletes the saved session with the given id. Returns whether it was found
+    pub fn delete_session(&self, session_id: &str) -> Result<bool, StatisticsError> {
+        let Some((path, _)) = self
+            .load_session_files()?
+            .into_iter()
+            .find(|(_, session)| session.session_id == session_id)
+        else {
+            return Ok(false);
+        };
+
+        fs::remove_file(path).map_err(StatisticsError::DeleteFile)?;
+        Ok(true)
+    }
+
+    // Allow unused for future use case, the history page aggregates the sessions it already loaded
     #[allow(unused)]
+    pub fn aggregate(&self) -> Result<AggregateStats, StatisticsError> {
+        Ok(AggregateStats::from_sessions(&self.load_all_sessions()?))
+    }
+
     pub fn load_sessions_for_config(
         &self,
         mode_name: &str,
@@ -176,3 +512,272 @@ impl StatisticsManager {
             .collect())
     }
 }
+
+/// Writes a session to `path` as JSON
+fn write_session(path: &Path, session: &SessionStatistics) -> Result<(), StatisticsError> {
+    let json = serde_json::to_string_pretty(session).map_err(StatisticsError::Parse)?;
+    fs::write(path, json).map_err(StatisticsError::WriteFile)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use gladius::TypingSession;
+
+    use crate::page::session::Mode;
+
+    use super::{
+        AggregateStats, EndReason, SESSION_SCHEMA_VERSION, SerializableStatistics, SessionConfig,
+        SessionExport, SessionStatistics, StatisticsManager,
+    };
+
+    fn statistics(wpm: f64, accuracy: f64, errors: usize) -> SerializableStatistics {
+        SerializableStatistics {
+            duration: 30.0,
+            wpm_actual: wpm,
+            wpm_raw: wpm + 5.0,
+            accuracy_actual: accuracy,
+            accuracy_raw: accuracy - 2.0,
+            consistency_actual_percent: 80.0,
+            adds: 100,
+            corrects: 90,
+            errors,
+            corrections: 0,
+            deletes: 0,
+            wrong_deletes: 0,
+        }
+    }
+
+    #[test]
+    fn diff_against_baseline() {
+        let baseline = statistics(60.0, 95.0, 4);
+        let current = statistics(63.0, 96.2, 1);
+
+        let diff = current.diff(&baseline);
+
+        assert!((diff.wpm_actual - 3.0).abs() < 1e-9);
+        assert!((diff.wpm_raw - 3.0).abs() < 1e-9);
+        assert!((diff.accuracy_actual - 1.2).abs() < 1e-9);
+        assert!(diff.consistency_actual_percent.abs() < 1e-9);
+        assert!((diff.errors + 3.0).abs() < 1e-9);
+
+        let reverse = baseline.diff(&current);
+        assert!((reverse.wpm_actual + 3.0).abs() < 1e-9);
+    }
+
+    fn session_json(duration: f64, wpm: f64, accuracy: f64, adds: usize, errors: usize) -> String {
+        format!(
+            r#"{{
+                "timestamp": {{ "secs_since_epoch": 1700000000, "nanos_since_epoch": 0 }},
+                "session_id": "test",
+                "session_config": {{
+                    "mode_name": "Normal",
+                    "source_name": "English",
+                    "time_limit": null,
+                    "words_typed_limit": 50,
+                    "allow_deletions": true,
+                    "allow_errors": true
+                }},
+                "statistics": {{
+                    "duration": {duration},
+                    "wpm_actual": {wpm},
+                    "wpm_raw": {wpm},
+                    "accuracy_actual": {accuracy},
+                    "accuracy_raw": {accuracy},
+                    "consistency_actual_percent": 80.0,
+                    "adds": {adds},
+                    "corrects": {adds},
+                    "errors": {errors},
+                    "corrections": 1,
+                    "deletes": 0,
+                    "wrong_deletes": 0
+                }}
+            }}"#
+        )
+    }
+
+    #[test]
+    fn aggregate_over_sessions() {
+        let sessions = [
+            session_json(60.0, 50.0, 90.0, 250, 5),
+            session_json(30.0, 80.0, 96.0, 200, 2),
+        ]
+        .iter()
+        .map(|json| serde_json::from_str::<SessionStatistics>(json).unwrap())
+        .collect::<Vec<_>>();
+
+        let aggregate = AggregateStats::from_sessions(&sessions);
+
+        assert_eq!(aggregate.session_count, 2);
+        assert!((aggregate.total_duration - 90.0).abs() < 1e-9);
+        assert_eq!(aggregate.total_characters, 450);
+        assert_eq!(aggregate.total_errors, 7);
+        assert_eq!(aggregate.total_corrections, 2);
+        // (50 * 60 + 80 * 30) / 90
+        assert!((aggregate.average_wpm - 60.0).abs() < 1e-9);
+        // (90 * 60 + 96 * 30) / 90
+        assert!((aggregate.average_accuracy - 92.0).abs() < 1e-9);
+        assert!((aggregate.best_wpm - 80.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn aggregate_without_sessions() {
+        assert_eq!(
+            AggregateStats::from_sessions(&[]),
+            AggregateStats::default()
+        );
+    }
+
+    #[test]
+    fn load_legacy_session_without_char_errors() {
+        let session =
+            serde_json::from_str::<SessionStatistics>(&session_json(60.0, 50.0, 90.0, 250, 5))
+                .unwrap();
+
+        assert_eq!(session.schema_version, 1);
+        assert!(session.char_errors.is_empty());
+        assert!(!session.completed);
+        assert_eq!(session.end_reason, EndReason::Unknown);
+        assert!(session.measurements.is_empty());
+    }
+
+    #[test]
+    fn char_errors_roundtrip_and_merge() {
+        let mut session =
+            serde_json::from_str::<SessionStatistics>(&session_json(60.0, 50.0, 90.0, 250, 5))
+                .unwrap();
+        session.schema_version = SESSION_SCHEMA_VERSION;
+        session.char_errors = HashMap::from([('z', 3), ('q', 1)]);
+        session.end_reason = EndReason::TimeLimit;
+
+        let json = serde_json::to_string(&session).unwrap();
+        let loaded = serde_json::from_str::<SessionStatistics>(&json).unwrap();
+        assert_eq!(loaded.schema_version, SESSION_SCHEMA_VERSION);
+        assert_eq!(loaded.char_errors, session.char_errors);
+        assert_eq!(loaded.end_reason, EndReason::TimeLimit);
+
+        let mut other = loaded.clone();
+        other.char_errors = HashMap::from([('q', 4), ('x', 1)]);
+
+        let aggregate = AggregateStats::from_sessions(&[loaded, other]);
+        assert_eq!(
+            aggregate.char_errors,
+            HashMap::from([('z', 3), ('q', 5), ('x', 1)])
+        );
+        assert_eq!(aggregate.most_missed(2), vec![('q', 5), ('z', 3)]);
+    }
+
+    #[test]
+    fn sessions_without_input_history_contain_no_char_errors() {
+        let directory =
+            std::env::temp_dir().join(format!("octotype-private-{}", std::process::id()));
+        let text = "secret";
+        let mut typing = TypingSession::new(text).unwrap();
+        // Mistype the 'c'
+        for character in "sexret".chars() {
+            typing.input(Some(character));
+        }
+        let statistics = typing.finalize();
+        let save = |manager: StatisticsManager| {
+            manager
+                .save_session(
+                    &Mode::from_text(text.to_string()),
+                    "Text".to_string(),
+                    "Custom text".to_string(),
+                    &statistics,
+                    EndReason::Finished,
+                )
+                .unwrap();
+            let session = manager.load_all_sessions().unwrap().remove(0);
+            let path = directory.join(format!(
+                "session_{}.json",
+                session
+                    .timestamp
+                    .duration_since(web_time::SystemTime::UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs()
+            ));
+            let json = std::fs::read_to_string(path).unwrap();
+            std::fs::remove_dir_all(&directory).unwrap();
+            json
+        };
+
+        let stored = save(StatisticsManager::new(directory.clone()).unwrap());
+        assert!(stored.contains("char_errors"));
+        assert!(stored.contains(r#""c""#) || stored.contains(r#""x""#));
+
+        let private = save(
+            StatisticsManager::new(directory.clone())
+                .unwrap()
+                .without_input_history(),
+        );
+        assert!(!private.contains("char_errors"));
+        assert!(!private.contains(r#""c""#) && !private.contains(r#""x""#));
+    }
+
+    #[test]
+    fn tags_are_saved_to_the_session() {
+        let directory = std::env::temp_dir().join(format!("octotype-tags-{}", std::process::id()));
+        let manager = StatisticsManager::new(directory.clone()).unwrap();
+        std::fs::write(
+            directory.join("session_test.json"),
+            session_json(60.0, 50.0, 90.0, 250, 5),
+        )
+        .unwrap();
+        assert!(manager.load_all_sessions().unwrap()[0].tags.is_empty());
+
+        let tags = ["tired".to_string(), "left-hand drill".to_string()];
+        assert!(manager.set_tags("test", &tags).unwrap());
+        assert!(!manager.set_tags("missing", &tags).unwrap());
+
+        assert_eq!(manager.load_all_sessions().unwrap()[0].tags, tags);
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn delete_session_by_id() {
+        let directory =
+            std::env::temp_dir().join(format!("octotype-statistics-{}", std::process::id()));
+        let manager = StatisticsManager::new(directory.clone()).unwrap();
+        for id in ["first", "second"] {
+            let json = session_json(60.0, 50.0, 90.0, 250, 5).replace(
+                r#""session_id": "test""#,
+                &format!(r#""session_id": "{id}""#),
+            );
+            std::fs::write(directory.join(format!("session_{id}.json")), json).unwrap();
+        }
+
+        assert!(manager.delete_session("first").unwrap());
+        assert!(!manager.delete_session("first").unwrap());
+
+        let remaining = manager.load_all_sessions().unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].session_id, "second");
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn exports_at_the_same_time_are_kept() {
+        let directory =
+            std::env::temp_dir().join(format!("octotype-exports-{}", std::process::id()));
+        let mode = Mode::from_text("abc".to_string());
+        let session_config =
+            SessionConfig::from_mode(&mode, "Text".to_string(), "Custom text".to_string());
+        let mut typing = TypingSession::new("abc").unwrap();
+        for character in "abc".chars() {
+            typing.input(Some(character));
+        }
+        let export = SessionExport::new(session_config, &typing.finalize());
+
+        let first = export.write(&directory).unwrap();
+        let second = export.write(&directory).unwrap();
+        let files = std::fs::read_dir(&directory).unwrap().count();
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(files, 2);
+    }
+}
@@ -0,0 +1,140 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use web_time::SystemTime;
+
+use super::StatisticsError;
+
+const STREAK_FILE: &str = "streak.json";
+
+/// Consecutive calendar days with at least one saved session
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Streak {
+    /// Days in a row, up to and including `last_day`
+    pub days: u32,
+    /// The longest streak so far
+    pub longest: u32,
+    /// The (local) day of the last saved session
+    pub last_day: Option<NaiveDate>,
+}
+
+impl Streak {
+    /// Counts a session on `day`
+    pub fn record(&mut self, day: NaiveDate) {
+        self.days = match self.last_day {
+            Some(last) if day <= last => return,
+            Some(last) if last.succ_opt() == Some(day) => self.days + 1,
+            _ => 1,
+        };
+        self.last_day = Some(day);
+        self.longest = self.longest.max(self.days);
+    }
+
+    /// The streak as of `today`. A streak is only broken once a whole day has been skipped, so
+    /// it still counts before the first session of the day
+    pub fn current(&self, today: NaiveDate) -> u32 {
+        match self.last_day {
+            Some(last) if last == today || last.succ_opt() == Some(today) => self.days,
+            _ => 0,
+        }
+    }
+}
+
+/// Loads and updates the [`Streak`], stored as `streak.json` in the statistics directory
+#[derive(Debug)]
+pub struct StreakTracker {
+    path: PathBuf,
+}
+
+impl StreakTracker {
+    pub fn new(directory: &Path) -> Self {
+        Self {
+            path: directory.join(STREAK_FILE),
+        }
+    }
+
+    /// Loads the streak, which is empty if nothing has been saved yet
+    pub fn load(&self) -> Result<Streak, StatisticsError> {
+        if !self.path.exists() {
+            return Ok(Streak::default());
+        }
+
+        let content = fs::read_to_string(&self.path).map_err(StatisticsError::ReadFile)?;
+        serde_json::from_str(&content).map_err(StatisticsError::Parse)
+    }
+
+    /// Counts a session saved at `timestamp`
+    pub fn record(&self, timestamp: SystemTime) -> Result<Streak, StatisticsError> {
+        let mut streak = self.load()?;
+        streak.record(local_day(timestamp));
+
+        let json = serde_json::to_string_pretty(&streak).map_err(StatisticsError::Parse)?;
+        fs::write(&self.path, json).map_err(StatisticsError::WriteFile)?;
+
+        Ok(streak)
+    }
+}
+
+/// The calendar day of `timestamp` in the local timezone
+pub fn local_day(timestamp: SystemTime) -> NaiveDate {
+    DateTime::<Local>::from(timestamp).date_naive()
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    use super::Streak;
+
+    fn day(day: u32) -> NaiveDate {
+        // Crosses the end of a month
+        NaiveDate::from_ymd_opt(2025, 1, 30).unwrap() + chrono::Days::new(u64::from(day))
+    }
+
+    #[test]
+    fn consecutive_days_extend_the_streak() {
+        let mut streak = Streak::default();
+        streak.record(day(0));
+        streak.record(day(0));
+        streak.record(day(1));
+        streak.record(day(2));
+
+        assert_eq!(streak.days, 3);
+        assert_eq!(streak.longest, 3);
+        assert_eq!(streak.last_day, Some(day(2)));
+    }
+
+    #[test]
+    fn skipped_day_resets_the_streak() {
+        let mut streak = Streak::default();
+        streak.record(day(0));
+        streak.record(day(1));
+        streak.record(day(3));
+
+        assert_eq!(streak.days, 1);
+        assert_eq!(streak.longest, 2);
+    }
+
+    #[test]
+    fn current_streak_survives_until_a_day_is_skipped() {
+        let mut streak = Streak::default();
+        streak.record(day(0));
+        streak.record(day(1));
+
+        assert_eq!(streak.current(day(1)), 2);
+        assert_eq!(streak.current(day(2)), 2);
+        assert_eq!(streak.current(day(3)), 0);
+    }
+
+    #[test]
+    fn older_sessions_are_ignored() {
+        let mut streak = Streak::default();
+        streak.record(day(5));
+        streak.record(day(1));
+
+        assert_eq!(streak.days, 1);
+        assert_eq!(streak.last_day, Some(day(5)));
+    }
+}
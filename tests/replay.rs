@@ -0,0 +1,28 @@
+use std::process::Command;
+
+#[test]
+fn replay_prints_only_json() {
+    let directory = std::env::temp_dir().join(format!("octotype-replay-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::write(directory.join("config.toml"), "bell_on_error = true\n").unwrap();
+    let events = directory.join("events.json");
+    // The 'x' is a mistake, which rings the bell in a normal session
+    std::fs::write(
+        &events,
+        r#"[[0, "a"], [0, "x"], [0, "Backspace"], [0, "b"]]"#,
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_octotype"))
+        .arg("--config")
+        .arg(&directory)
+        .args(["--no-bootstrap", "--text", "ab", "--replay"])
+        .arg(&events)
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&directory).unwrap();
+
+    assert!(output.status.success());
+    let export: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(export["statistics"]["errors"], 1);
+}